@@ -0,0 +1,494 @@
+use crate::BigUInt;
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// number of bits needed to represent the value, zero for zero
+    pub fn bits(&self) -> u32 {
+        (64 * LIMBS) as u32 - self.leading_zeros()
+    }
+
+    /// number of zero bits above the most significant one bit
+    pub fn leading_zeros(&self) -> u32 {
+        let mut zeros = (64 * (LIMBS - self.len)) as u32;
+        for chunk in self.digits().iter().rev() {
+            zeros += chunk.leading_zeros();
+            if *chunk != 0 {
+                break;
+            }
+        }
+        zeros
+    }
+
+    /// number of zero bits below the least significant one bit, the full width for zero
+    pub fn trailing_zeros(&self) -> u32 {
+        let mut zeros = 0;
+        for chunk in self.chunks.iter() {
+            zeros += chunk.trailing_zeros();
+            if *chunk != 0 {
+                break;
+            }
+        }
+        zeros
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.digits().iter().map(|chunk| chunk.count_ones()).sum()
+    }
+
+    /// returns bit `index`, counting from the least significant bit; bits beyond the width are zero
+    pub fn bit(&self, index: u32) -> bool {
+        let chunk = (index / 64) as usize;
+        chunk < LIMBS && (self.chunks[chunk] >> (index % 64)) & 1 == 1
+    }
+
+    /// sets bit `index` to `value`, panicking if the index is beyond the width
+    pub fn set_bit(&mut self, index: u32, value: bool) {
+        let chunk = (index / 64) as usize;
+        if chunk >= LIMBS {
+            panic!("Bit index out of range");
+        }
+        let mask = 1u64 << (index % 64);
+        if value {
+            self.chunks[chunk] |= mask;
+            self.len = self.len.max(chunk + 1);
+        } else {
+            self.chunks[chunk] &= !mask;
+            self.trim_len(self.len);
+        }
+    }
+
+    /// shifts left in place by `shift` bits, discarding bits shifted beyond the width
+    fn shl_assign_bits(&mut self, shift: u32) {
+        let chunk_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        // work downwards, so that every chunk is read before it is overwritten
+        for i in (0..LIMBS).rev() {
+            self.chunks[i] = if i >= chunk_shift {
+                let j = i - chunk_shift;
+                let mut chunk = self.chunks[j] << bit_shift;
+                // bits carried over from the chunk below
+                if bit_shift > 0 && j > 0 {
+                    chunk |= self.chunks[j - 1] >> (64 - bit_shift);
+                }
+                chunk
+            } else {
+                0
+            };
+        }
+        self.trim_len(self.len + chunk_shift + 1);
+    }
+
+    /// shifts right in place by `shift` bits, discarding bits shifted below zero
+    fn shr_assign_bits(&mut self, shift: u32) {
+        let chunk_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        // work upwards, so that every chunk is read before it is overwritten
+        for i in 0..LIMBS {
+            self.chunks[i] = if i + chunk_shift < LIMBS {
+                let j = i + chunk_shift;
+                let mut chunk = self.chunks[j] >> bit_shift;
+                // bits carried over from the chunk above
+                if bit_shift > 0 && j + 1 < LIMBS {
+                    chunk |= self.chunks[j + 1] << (64 - bit_shift);
+                }
+                chunk
+            } else {
+                0
+            };
+        }
+        self.trim_len(self.len.saturating_sub(chunk_shift));
+    }
+
+    fn shl_bits(&self, shift: u32) -> Self {
+        let mut result = self.clone();
+        result.shl_assign_bits(shift);
+        result
+    }
+
+    fn shr_bits(&self, shift: u32) -> Self {
+        let mut result = self.clone();
+        result.shr_assign_bits(shift);
+        result
+    }
+
+    /// applies `op` chunkwise; it must map a pair of zero chunks to zero
+    fn zip_assign_chunks(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) {
+        let len = self.len.max(other.len);
+        for i in 0..len {
+            self.chunks[i] = op(self.chunks[i], other.chunks[i]);
+        }
+        self.trim_len(len);
+    }
+
+    fn zip_chunks(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut result = self.clone();
+        result.zip_assign_chunks(other, op);
+        result
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAnd<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitand(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one & other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAnd<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitand(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one & other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAnd<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitand(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one & other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAnd<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitand(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one & other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOr<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitor(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one | other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOr<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitor(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one | other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOr<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitor(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one | other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOr<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitor(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one | other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXor<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitxor(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one ^ other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXor<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn bitxor(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one ^ other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXor<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitxor(self, other: BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(&other, |one, other| one ^ other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXor<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn bitxor(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        self.zip_chunks(other, |one, other| one ^ other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Shl<u32> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn shl(self, shift: u32) -> Self::Output {
+        self.shl_bits(shift)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Shl<u32> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn shl(self, shift: u32) -> Self::Output {
+        self.shl_bits(shift)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Shr<u32> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn shr(self, shift: u32) -> Self::Output {
+        self.shr_bits(shift)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Shr<u32> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn shr(self, shift: u32) -> Self::Output {
+        self.shr_bits(shift)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Not for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        let mut result = BigUInt::default();
+        for i in 0..LIMBS {
+            result.chunks[i] = !self.chunks[i];
+        }
+        result.trim_len(LIMBS);
+        result
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Not for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn not(self) -> Self::Output {
+        let mut result = BigUInt::default();
+        for i in 0..LIMBS {
+            result.chunks[i] = !self.chunks[i];
+        }
+        result.trim_len(LIMBS);
+        result
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAndAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitand_assign(&mut self, other: BigUInt<LIMBS>) {
+        self.zip_assign_chunks(&other, |one, other| one & other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitAndAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitand_assign(&mut self, other: &BigUInt<LIMBS>) {
+        self.zip_assign_chunks(other, |one, other| one & other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOrAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitor_assign(&mut self, other: BigUInt<LIMBS>) {
+        self.zip_assign_chunks(&other, |one, other| one | other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitOrAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitor_assign(&mut self, other: &BigUInt<LIMBS>) {
+        self.zip_assign_chunks(other, |one, other| one | other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXorAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitxor_assign(&mut self, other: BigUInt<LIMBS>) {
+        self.zip_assign_chunks(&other, |one, other| one ^ other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::BitXorAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn bitxor_assign(&mut self, other: &BigUInt<LIMBS>) {
+        self.zip_assign_chunks(other, |one, other| one ^ other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::ShlAssign<u32> for BigUInt<LIMBS> {
+    fn shl_assign(&mut self, shift: u32) {
+        self.shl_assign_bits(shift);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::ShrAssign<u32> for BigUInt<LIMBS> {
+    fn shr_assign(&mut self, shift: u32) {
+        self.shr_assign_bits(shift);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test bit queries on zero, small and full-width values
+    #[test]
+    fn bit_query_test() {
+        let zero = BigUInt4096::default();
+        assert_eq!(zero.bits(), 0);
+        assert_eq!(zero.leading_zeros(), 4096);
+        assert_eq!(zero.trailing_zeros(), 4096);
+        assert_eq!(zero.count_ones(), 0);
+        let num = BigUInt4096::new_from_middle(&[0x10u64, 0x0u64, 0x3u64], 5);
+        assert_eq!(num.bits(), 64 * 7 + 2);
+        assert_eq!(num.leading_zeros(), 4096 - (64 * 7 + 2));
+        assert_eq!(num.trailing_zeros(), 64 * 5 + 4);
+        assert_eq!(num.count_ones(), 3);
+        let full = BigUInt4096::new(&[u64::MAX; 64]);
+        assert_eq!(full.bits(), 4096);
+        assert_eq!(full.leading_zeros(), 0);
+        assert_eq!(full.count_ones(), 4096);
+    }
+
+    // test reading and writing single bits
+    #[test]
+    fn bit_test() {
+        let mut num = BigUInt4096::default();
+        num.set_bit(0, true);
+        num.set_bit(200, true);
+        num.set_bit(4095, true);
+        assert_eq!(
+            num,
+            BigUInt4096::new_from_middle(&[1u64, 0u64, 0u64, 0x100u64], 0)
+                | BigUInt4096::new_from_right(&[0x8000000000000000u64])
+        );
+        assert!(num.bit(200));
+        assert!(!num.bit(201));
+        assert!(!num.bit(5000));
+        num.set_bit(200, false);
+        assert!(!num.bit(200));
+        assert_eq!(num.count_ones(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_bit_test() {
+        let mut num = BigUInt256::default();
+        num.set_bit(256, true);
+    }
+
+    #[test]
+    fn shl_test() {
+        let num1 = BigUInt4096::new(&[0x8000000000000001u64, 0x1u64]);
+        let num2 = BigUInt4096::new_from_middle(&[0x4u64, 0x6u64], 1);
+        assert_eq!(num1.clone() << 66, num2);
+        assert_eq!(&num1 << 66, num2);
+        assert_eq!(&num1 << 0, num1);
+        assert_eq!(
+            &num1 << 64,
+            BigUInt4096::new(&[0x0u64, 0x8000000000000001u64, 0x1u64])
+        );
+    }
+
+    // test that bits shifted beyond the width are discarded
+    #[test]
+    fn shl_overflow_test() {
+        let num1 = BigUInt256::new(&[u64::MAX; 4]);
+        assert_eq!(
+            &num1 << 255,
+            BigUInt256::new_from_right(&[0x8000000000000000u64])
+        );
+        assert_eq!(&num1 << 256, BigUInt256::default());
+        assert_eq!(&num1 << 1000, BigUInt256::default());
+    }
+
+    #[test]
+    fn shr_test() {
+        let num1 = BigUInt4096::new_from_middle(&[0x4u64, 0x6u64], 1);
+        let num2 = BigUInt4096::new(&[0x8000000000000001u64, 0x1u64]);
+        assert_eq!(num1.clone() >> 66, num2);
+        assert_eq!(&num1 >> 66, num2);
+        assert_eq!(&num1 >> 0, num1);
+        assert_eq!(&num1 >> 64, BigUInt4096::new(&[0x4u64, 0x6u64]));
+    }
+
+    // test that bits shifted below zero are discarded
+    #[test]
+    fn shr_underflow_test() {
+        let num1 = BigUInt256::new(&[u64::MAX; 4]);
+        assert_eq!(&num1 >> 255, BigUInt256::new(&[0x1u64]));
+        assert_eq!(&num1 >> 256, BigUInt256::default());
+        assert_eq!(&num1 >> 1000, BigUInt256::default());
+    }
+
+    #[test]
+    fn bitand_test() {
+        let num1 = BigUInt4096::new(&[0xff00u64, 0xf0u64]);
+        let num2 = BigUInt4096::new(&[0x0ff0u64, 0xffu64, 0x1u64]);
+        let num3 = BigUInt4096::new(&[0x0f00u64, 0xf0u64]);
+        assert_eq!(num1.clone() & num2.clone(), num3);
+        assert_eq!(num1.clone() & &num2, num3);
+        assert_eq!(&num1 & num2.clone(), num3);
+        assert_eq!(&num1 & &num2, num3);
+    }
+
+    #[test]
+    fn bitor_test() {
+        let num1 = BigUInt4096::new(&[0xff00u64, 0xf0u64]);
+        let num2 = BigUInt4096::new(&[0x0ff0u64, 0xffu64, 0x1u64]);
+        let num3 = BigUInt4096::new(&[0xfff0u64, 0xffu64, 0x1u64]);
+        assert_eq!(num1.clone() | num2.clone(), num3);
+        assert_eq!(num1.clone() | &num2, num3);
+        assert_eq!(&num1 | num2.clone(), num3);
+        assert_eq!(&num1 | &num2, num3);
+    }
+
+    #[test]
+    fn bitxor_test() {
+        let num1 = BigUInt4096::new(&[0xff00u64, 0xf0u64]);
+        let num2 = BigUInt4096::new(&[0x0ff0u64, 0xffu64, 0x1u64]);
+        let num3 = BigUInt4096::new(&[0xf0f0u64, 0x0fu64, 0x1u64]);
+        assert_eq!(num1.clone() ^ num2.clone(), num3);
+        assert_eq!(num1.clone() ^ &num2, num3);
+        assert_eq!(&num1 ^ num2.clone(), num3);
+        assert_eq!(&num1 ^ &num2, num3);
+    }
+
+    #[test]
+    fn not_test() {
+        let num1 = BigUInt256::new(&[0x0u64, u64::MAX, 0xf0u64]);
+        let num2 = BigUInt256::new(&[u64::MAX, 0x0u64, !0xf0u64, u64::MAX]);
+        assert_eq!(!num1.clone(), num2);
+        assert_eq!(!&num1, num2);
+        assert_eq!(!!num1.clone(), num1);
+    }
+
+    #[test]
+    fn shift_assign_test() {
+        let mut num1 = BigUInt4096::new(&[0x8000000000000001u64, 0x1u64]);
+        num1 <<= 66;
+        assert_eq!(num1, BigUInt4096::new_from_middle(&[0x4u64, 0x6u64], 1));
+        num1 >>= 66;
+        assert_eq!(num1, BigUInt4096::new(&[0x8000000000000001u64, 0x1u64]));
+        num1 <<= 4095;
+        assert_eq!(num1, BigUInt4096::new_from_right(&[0x8000000000000000u64]));
+        num1 >>= 4096;
+        assert_eq!(num1, BigUInt4096::default());
+    }
+
+    #[test]
+    fn bit_assign_test() {
+        let num1 = BigUInt4096::new(&[0xff00u64, 0xf0u64]);
+        let num2 = BigUInt4096::new(&[0x0ff0u64, 0xffu64, 0x1u64]);
+        let mut num3 = num1.clone();
+        num3 &= &num2;
+        assert_eq!(num3, BigUInt4096::new(&[0x0f00u64, 0xf0u64]));
+        num3 |= num2.clone();
+        assert_eq!(num3, num2);
+        num3 ^= &num1;
+        assert_eq!(num3, BigUInt4096::new(&[0xf0f0u64, 0x0fu64, 0x1u64]));
+        num3 ^= num1.clone();
+        assert_eq!(num3, num2);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::BigUInt;
+use std::fmt;
+
+/// error returned when a byte string holds a value too large for the integer width
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FromBytesError;
+
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte string too large to fit in target type")
+    }
+}
+
+impl std::error::Error for FromBytesError {}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// builds a value from little-endian bytes; input longer than the width is accepted
+    /// as long as the extra bytes are zero
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        let len = bytes.len().min(8 * LIMBS);
+        if bytes[len..].iter().any(|&byte| byte != 0) {
+            return Err(FromBytesError);
+        }
+        let mut newint = Self::default();
+        for (i, chunk_bytes) in bytes[..len].chunks(8).enumerate() {
+            let mut buf = [0u8; 8];
+            buf[..chunk_bytes.len()].copy_from_slice(chunk_bytes);
+            newint.chunks[i] = u64::from_le_bytes(buf);
+        }
+        newint.trim_len(len.div_ceil(8));
+        Ok(newint)
+    }
+
+    /// builds a value from big-endian bytes; input longer than the width is accepted
+    /// as long as the extra bytes are zero
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        let mut reversed = bytes.to_vec();
+        reversed.reverse();
+        Self::from_le_bytes(&reversed)
+    }
+
+    /// little-endian bytes, always `8 * LIMBS` of them
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.chunks
+            .iter()
+            .flat_map(|chunk| chunk.to_le_bytes())
+            .collect()
+    }
+
+    /// big-endian bytes, always `8 * LIMBS` of them
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.chunks
+            .iter()
+            .rev()
+            .flat_map(|chunk| chunk.to_be_bytes())
+            .collect()
+    }
+
+    /// little-endian bytes without trailing zeros; zero is encoded as a single zero byte
+    pub fn to_le_bytes_minimal(&self) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes();
+        let len = bytes
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(1, |i| i + 1);
+        bytes.truncate(len);
+        bytes
+    }
+
+    /// big-endian bytes without leading zeros; zero is encoded as a single zero byte
+    pub fn to_be_bytes_minimal(&self) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes_minimal();
+        bytes.reverse();
+        bytes
+    }
+}
+
+/// serialises as a lowercase hex string without prefix
+#[cfg(feature = "serde")]
+impl<const LIMBS: usize> serde::Serialize for BigUInt<LIMBS> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_str_radix(16))
+    }
+}
+
+/// deserialises from a hex string, with or without a `0x` prefix
+#[cfg(feature = "serde")]
+impl<'de, const LIMBS: usize> serde::Deserialize<'de> for BigUInt<LIMBS> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HexVisitor<const LIMBS: usize>;
+
+        impl<const LIMBS: usize> serde::de::Visitor<'_> for HexVisitor<LIMBS> {
+            type Value = BigUInt<LIMBS>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a hex string of at most {} bits", 64 * LIMBS)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                let digits = value.strip_prefix("0x").unwrap_or(value);
+                BigUInt::from_str_radix(digits, 16).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::FromBytesError;
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test conversion from bytes in both byte orders
+    #[test]
+    fn from_bytes_test() {
+        let num = BigUInt4096::new(&[0x0123456789abcdefu64, 0x1122u64]);
+        let be = [0x11u8, 0x22, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        let mut le = be;
+        le.reverse();
+        assert_eq!(BigUInt4096::from_be_bytes(&be), Ok(num.clone()));
+        assert_eq!(BigUInt4096::from_le_bytes(&le), Ok(num));
+        assert_eq!(BigUInt4096::from_be_bytes(&[]), Ok(BigUInt4096::default()));
+    }
+
+    // test that input longer than the width is only accepted when the extra bytes are zero
+    #[test]
+    fn from_bytes_oversize_test() {
+        let mut bytes = vec![0u8; 40];
+        bytes[8] = 0x1;
+        assert_eq!(
+            BigUInt256::from_be_bytes(&bytes),
+            Ok(BigUInt256::new_from_right(&[0x1u64 << 56]))
+        );
+        bytes[7] = 0x1;
+        assert_eq!(BigUInt256::from_be_bytes(&bytes), Err(FromBytesError));
+        assert_eq!(
+            BigUInt256::from_le_bytes(&[0xffu8; 33]),
+            Err(FromBytesError)
+        );
+        assert_eq!(
+            BigUInt256::from_le_bytes(&[0xffu8; 32]),
+            Ok(BigUInt256::new(&[u64::MAX; 4]))
+        );
+    }
+
+    // test conversion to full-width and minimal-length bytes
+    #[test]
+    fn to_bytes_test() {
+        let num = BigUInt256::new(&[0x0123456789abcdefu64, 0x1122u64]);
+        let be = num.to_be_bytes();
+        assert_eq!(be.len(), 32);
+        assert_eq!(be[..22], [0u8; 22]);
+        assert_eq!(
+            be[22..],
+            [0x11u8, 0x22, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]
+        );
+        let mut le = num.to_le_bytes();
+        le.reverse();
+        assert_eq!(le, be);
+        assert_eq!(num.to_be_bytes_minimal(), be[22..]);
+        assert_eq!(num.to_le_bytes_minimal().len(), 10);
+        assert_eq!(BigUInt256::default().to_be_bytes_minimal(), vec![0u8]);
+        assert_eq!(BigUInt256::from_be_bytes(&be), Ok(num.clone()));
+        assert_eq!(
+            BigUInt256::from_le_bytes(&num.to_le_bytes_minimal()),
+            Ok(num)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let num = BigUInt4096::new(&[0x0123456789abcdefu64, 0x1122u64]);
+        let json = serde_json::to_string(&num).unwrap();
+        assert_eq!(json, "\"11220123456789abcdef\"");
+        assert_eq!(serde_json::from_str::<BigUInt4096>(&json).unwrap(), num);
+        assert_eq!(
+            serde_json::from_str::<BigUInt4096>("\"0x11220123456789ABCDEF\"").unwrap(),
+            num
+        );
+        assert!(serde_json::from_str::<BigUInt256>(&format!("\"{}\"", "f".repeat(65))).is_err());
+        assert!(serde_json::from_str::<BigUInt256>("\"xyz\"").is_err());
+        assert!(serde_json::from_str::<BigUInt256>("12").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::BigUInt;
+use std::fmt;
+
+/// error returned when a `BigUInt` does not fit in the target primitive type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromBigUIntError;
+
+impl fmt::Display for TryFromBigUIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "out of range integral type conversion attempted")
+    }
+}
+
+impl std::error::Error for TryFromBigUIntError {}
+
+impl<const LIMBS: usize> From<u8> for BigUInt<LIMBS> {
+    fn from(value: u8) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl<const LIMBS: usize> From<u16> for BigUInt<LIMBS> {
+    fn from(value: u16) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl<const LIMBS: usize> From<u32> for BigUInt<LIMBS> {
+    fn from(value: u32) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for BigUInt<LIMBS> {
+    fn from(value: u64) -> Self {
+        let mut newint = Self::ZERO;
+        newint.chunks[0] = value;
+        newint.trim_len(1);
+        newint
+    }
+}
+
+impl<const LIMBS: usize> From<u128> for BigUInt<LIMBS> {
+    fn from(value: u128) -> Self {
+        const { assert!(LIMBS >= 2, "A u128 needs at least two chunks") };
+        let mut newint = Self::ZERO;
+        newint.chunks[0] = value as u64;
+        newint.chunks[1] = (value >> 64) as u64;
+        newint.trim_len(2);
+        newint
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUInt<LIMBS>> for u128 {
+    type Error = TryFromBigUIntError;
+
+    fn try_from(value: BigUInt<LIMBS>) -> Result<Self, Self::Error> {
+        if value.bits() > 128 {
+            return Err(TryFromBigUIntError);
+        }
+        let high = value.chunks.get(1).copied().unwrap_or(0);
+        Ok((high as u128) << 64 | value.chunks[0] as u128)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUInt<LIMBS>> for u64 {
+    type Error = TryFromBigUIntError;
+
+    fn try_from(value: BigUInt<LIMBS>) -> Result<Self, Self::Error> {
+        if value.bits() > 64 {
+            return Err(TryFromBigUIntError);
+        }
+        Ok(value.chunks[0])
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUInt<LIMBS>> for u32 {
+    type Error = TryFromBigUIntError;
+
+    fn try_from(value: BigUInt<LIMBS>) -> Result<Self, Self::Error> {
+        u64::try_from(value)?
+            .try_into()
+            .map_err(|_| TryFromBigUIntError)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUInt<LIMBS>> for u16 {
+    type Error = TryFromBigUIntError;
+
+    fn try_from(value: BigUInt<LIMBS>) -> Result<Self, Self::Error> {
+        u64::try_from(value)?
+            .try_into()
+            .map_err(|_| TryFromBigUIntError)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUInt<LIMBS>> for u8 {
+    type Error = TryFromBigUIntError;
+
+    fn try_from(value: BigUInt<LIMBS>) -> Result<Self, Self::Error> {
+        u64::try_from(value)?
+            .try_into()
+            .map_err(|_| TryFromBigUIntError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TryFromBigUIntError;
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test conversions from every primitive width
+    #[test]
+    fn from_primitive_test() {
+        assert_eq!(BigUInt4096::from(0xabu8), BigUInt4096::new(&[0xab]));
+        assert_eq!(BigUInt4096::from(0xabcdu16), BigUInt4096::new(&[0xabcd]));
+        assert_eq!(BigUInt4096::from(u32::MAX), BigUInt4096::new(&[0xffffffff]));
+        assert_eq!(BigUInt4096::from(u64::MAX), BigUInt4096::new(&[u64::MAX]));
+        assert_eq!(
+            BigUInt4096::from(0x0123456789abcdef_fedcba9876543210u128),
+            BigUInt4096::new(&[0xfedcba9876543210, 0x0123456789abcdef])
+        );
+        let value: BigUInt256 = 7u32.into();
+        assert_eq!(value, BigUInt256::new(&[7]));
+    }
+
+    // test conversions back to primitives succeed exactly when the value fits
+    #[test]
+    fn try_into_primitive_test() {
+        let value = BigUInt4096::from(300u64);
+        assert_eq!(u8::try_from(value.clone()), Err(TryFromBigUIntError));
+        assert_eq!(u16::try_from(value.clone()), Ok(300));
+        assert_eq!(u32::try_from(value.clone()), Ok(300));
+        assert_eq!(u64::try_from(value.clone()), Ok(300));
+        assert_eq!(u128::try_from(value), Ok(300));
+        assert_eq!(u8::try_from(BigUInt4096::from(u8::MAX)), Ok(u8::MAX));
+        assert_eq!(
+            u32::try_from(BigUInt4096::from(1u64 << 32)),
+            Err(TryFromBigUIntError)
+        );
+        let wide = BigUInt4096::from(u128::MAX);
+        assert_eq!(u128::try_from(wide.clone()), Ok(u128::MAX));
+        assert_eq!(u64::try_from(wide.clone()), Err(TryFromBigUIntError));
+        assert_eq!(
+            u128::try_from(wide + BigUInt4096::ONE),
+            Err(TryFromBigUIntError)
+        );
+        assert_eq!(
+            u64::try_from(BigUInt4096::new(&[0, 0, 1])),
+            Err(TryFromBigUIntError)
+        );
+    }
+
+    // test the ZERO, ONE and MAX constants
+    #[test]
+    fn constants_test() {
+        assert!(BigUInt4096::ZERO.is_zero());
+        assert_eq!(BigUInt4096::ZERO, BigUInt4096::default());
+        assert!(BigUInt4096::ONE.is_one());
+        assert_eq!(BigUInt256::MAX, !BigUInt256::ZERO);
+        assert_eq!(BigUInt256::MAX.count_ones(), 256);
+        assert_eq!(
+            BigUInt256::MAX.overflowing_add(&BigUInt256::ONE),
+            (BigUInt256::ZERO, true)
+        );
+    }
+}
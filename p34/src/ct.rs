@@ -0,0 +1,279 @@
+//! Constant-time operations, for values such as secret keys and exponents.
+//!
+//! None of these routines branch or index memory on the values they are given: loops always
+//! run over the full width, and carries and choices are folded in with arithmetic and masks.
+//! Their results are marked as using every chunk, so the significant length is never worked
+//! out from secret data. `PartialEq` and the plain operators do not give this guarantee.
+
+use crate::BigUInt;
+use subtle::{
+    Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess,
+};
+
+/// records the control flow of the constant-time routines while testing, so that runs on
+/// different secret inputs can be compared; this checks the path through the source code,
+/// not the instructions the compiler emits for it
+#[cfg(test)]
+pub(crate) mod trace {
+    use std::cell::Cell;
+
+    thread_local! {
+        static PATH: Cell<(u64, u64)> = const { Cell::new((0, 0xcbf29ce484222325)) };
+    }
+
+    /// folds a call-site identifier into the running step count and FNV-1a hash
+    pub(crate) fn step(site: u64) {
+        PATH.with(|path| {
+            let (count, hash) = path.get();
+            path.set((count + 1, (hash ^ site).wrapping_mul(0x100000001b3)));
+        });
+    }
+
+    /// returns the recorded path and starts a new one
+    pub(crate) fn take() -> (u64, u64) {
+        PATH.with(|path| path.replace((0, 0xcbf29ce484222325)))
+    }
+}
+
+#[inline(always)]
+pub(crate) fn step(_site: u64) {
+    #[cfg(test)]
+    trace::step(_site);
+}
+
+/// adds `other` into `sum` over their full equal length, returning the carry as 0 or 1
+pub(crate) fn ct_add_chunks(sum: &mut [u64], other: &[u64]) -> u64 {
+    let mut carry: u64 = 0;
+    for i in 0..sum.len() {
+        step(1);
+        let acc = sum[i] as u128 + other[i] as u128 + carry as u128;
+        sum[i] = acc as u64;
+        carry = (acc >> 64) as u64;
+    }
+    carry
+}
+
+/// subtracts `other` from `diff` over their full equal length, returning the borrow as 0 or 1
+pub(crate) fn ct_sub_chunks(diff: &mut [u64], other: &[u64]) -> u64 {
+    let mut borrow: u64 = 0;
+    for i in 0..diff.len() {
+        step(2);
+        let acc = (diff[i] as u128)
+            .wrapping_sub(other[i] as u128)
+            .wrapping_sub(borrow as u128);
+        diff[i] = acc as u64;
+        borrow = (acc >> 127) as u64;
+    }
+    borrow
+}
+
+/// schoolbook product of `one` and `other` into `prod`, without skipping zero chunks
+pub(crate) fn ct_mul_chunks(one: &[u64], other: &[u64], prod: &mut [u64]) {
+    prod.fill(0);
+    for (i, &chunk) in one.iter().enumerate() {
+        let mut carry: u64 = 0;
+        for (j, &other_chunk) in other.iter().enumerate() {
+            step(3);
+            let acc = prod[i + j] as u128 + chunk as u128 * other_chunk as u128 + carry as u128;
+            prod[i + j] = acc as u64;
+            carry = (acc >> 64) as u64;
+        }
+        prod[i + other.len()] = carry;
+    }
+}
+
+/// `Choice` that is set exactly when every chunk is zero
+pub(crate) fn ct_all_zero(chunks: &[u64]) -> Choice {
+    let mut acc: u64 = 0;
+    for &chunk in chunks {
+        step(4);
+        acc |= chunk;
+    }
+    acc.ct_eq(&0)
+}
+
+impl<const LIMBS: usize> ConstantTimeEq for BigUInt<LIMBS> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let mut acc: u64 = 0;
+        for i in 0..LIMBS {
+            step(5);
+            acc |= self.chunks[i] ^ other.chunks[i];
+        }
+        acc.ct_eq(&0)
+    }
+}
+
+impl<const LIMBS: usize> ConstantTimeGreater for BigUInt<LIMBS> {
+    fn ct_gt(&self, other: &Self) -> Choice {
+        // self > other exactly when other - self borrows
+        let mut diff = other.chunks;
+        Choice::from(ct_sub_chunks(&mut diff, &self.chunks) as u8)
+    }
+}
+
+impl<const LIMBS: usize> ConstantTimeLess for BigUInt<LIMBS> {}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// returns `one` if `choice` is unset and `other` if it is set
+    pub fn conditional_select(one: &Self, other: &Self, choice: Choice) -> Self {
+        let mut result = Self::default();
+        for i in 0..LIMBS {
+            step(6);
+            result.chunks[i] = u64::conditional_select(&one.chunks[i], &other.chunks[i], choice);
+        }
+        result.len = LIMBS;
+        result
+    }
+
+    /// overwrites `self` with `other` if `choice` is set
+    pub fn conditional_assign(&mut self, other: &Self, choice: Choice) {
+        for i in 0..LIMBS {
+            step(7);
+            self.chunks[i].conditional_assign(&other.chunks[i], choice);
+        }
+        self.len = LIMBS;
+    }
+
+    /// swaps `one` and `other` if `choice` is set
+    pub fn conditional_swap(one: &mut Self, other: &mut Self, choice: Choice) {
+        for i in 0..LIMBS {
+            step(8);
+            u64::conditional_swap(&mut one.chunks[i], &mut other.chunks[i], choice);
+        }
+        one.len = LIMBS;
+        other.len = LIMBS;
+    }
+
+    /// constant-time `overflowing_add`: returns the wrapped sum and whether it overflowed
+    pub fn ct_add(&self, other: &Self) -> (Self, Choice) {
+        let mut sum = self.clone();
+        let carry = ct_add_chunks(&mut sum.chunks, &other.chunks);
+        sum.len = LIMBS;
+        (sum, Choice::from(carry as u8))
+    }
+
+    /// constant-time `overflowing_sub`: returns the wrapped difference and whether it borrowed
+    pub fn ct_sub(&self, other: &Self) -> (Self, Choice) {
+        let mut diff = self.clone();
+        let borrow = ct_sub_chunks(&mut diff.chunks, &other.chunks);
+        diff.len = LIMBS;
+        (diff, Choice::from(borrow as u8))
+    }
+
+    /// constant-time `overflowing_mul`: returns the low half of the product and whether
+    /// the high half was non-zero
+    pub fn ct_mul(&self, other: &Self) -> (Self, Choice) {
+        let mut prod = [[0u64; LIMBS]; 2];
+        ct_mul_chunks(&self.chunks, &other.chunks, prod.as_flattened_mut());
+        let [low, high] = prod;
+        (Self::from_chunks_full(low), !ct_all_zero(&high))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ct::trace;
+    use crate::{BigUInt256, BigUInt4096};
+    use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+    #[test]
+    fn ct_eq_test() {
+        let num1 = BigUInt4096::new(&[0x1u64, 0x2u64]);
+        let num2 = BigUInt4096::new(&[0x1u64, 0x3u64]);
+        assert!(bool::from(num1.ct_eq(&num1.clone())));
+        assert!(!bool::from(num1.ct_eq(&num2)));
+        assert!(!bool::from(
+            num1.ct_eq(&BigUInt4096::new_from_right(&[0x1u64]))
+        ));
+    }
+
+    #[test]
+    fn ct_lt_gt_test() {
+        let num1 = BigUInt4096::new(&[u64::MAX, 0x0u64]);
+        let num2 = BigUInt4096::new(&[0x0u64, 0x1u64]);
+        assert!(bool::from(num1.ct_lt(&num2)));
+        assert!(!bool::from(num2.ct_lt(&num1)));
+        assert!(bool::from(num2.ct_gt(&num1)));
+        assert!(!bool::from(num1.ct_gt(&num2)));
+        assert!(!bool::from(num1.ct_lt(&num1.clone())));
+        assert!(!bool::from(num1.ct_gt(&num1.clone())));
+    }
+
+    #[test]
+    fn conditional_select_swap_test() {
+        let num1 = BigUInt256::new(&[0x1u64, 0x2u64]);
+        let num2 = BigUInt256::new(&[0x3u64, 0x4u64, 0x5u64]);
+        assert_eq!(
+            BigUInt256::conditional_select(&num1, &num2, Choice::from(0)),
+            num1
+        );
+        assert_eq!(
+            BigUInt256::conditional_select(&num1, &num2, Choice::from(1)),
+            num2
+        );
+        let mut num3 = num1.clone();
+        num3.conditional_assign(&num2, Choice::from(0));
+        assert_eq!(num3, num1);
+        num3.conditional_assign(&num2, Choice::from(1));
+        assert_eq!(num3, num2);
+        let (mut one, mut other) = (num1.clone(), num2.clone());
+        BigUInt256::conditional_swap(&mut one, &mut other, Choice::from(0));
+        assert_eq!((&one, &other), (&num1, &num2));
+        BigUInt256::conditional_swap(&mut one, &mut other, Choice::from(1));
+        assert_eq!((&one, &other), (&num2, &num1));
+    }
+
+    // test that the constant-time arithmetic agrees with the plain versions
+    #[test]
+    fn ct_arithmetic_test() {
+        let max = BigUInt256::new(&[u64::MAX; 4]);
+        let num1 = BigUInt256::new(&[u64::MAX, 0x1u64]);
+        let num2 = BigUInt256::new_from_right(&[0x2u64, 0x0u64]);
+        for (one, other) in [
+            (&num1, &num2),
+            (&num2, &num1),
+            (&max, &num1),
+            (&num2, &num2),
+        ] {
+            let (sum, carry) = one.ct_add(other);
+            assert_eq!((sum, bool::from(carry)), one.overflowing_add(other));
+            let (diff, borrow) = one.ct_sub(other);
+            assert_eq!((diff, bool::from(borrow)), one.overflowing_sub(other));
+            let (prod, overflow) = one.ct_mul(other);
+            assert_eq!((prod, bool::from(overflow)), one.overflowing_mul(other));
+        }
+    }
+
+    // test that the steps taken do not depend on the values, for very different inputs
+    #[test]
+    fn ct_path_test() {
+        let zero = BigUInt4096::default();
+        let one = BigUInt4096::new(&[0x1u64]);
+        let max = BigUInt4096::new(&[u64::MAX; 64]);
+        let mixed = BigUInt4096::new_from_middle(&[u64::MAX, 0x0u64, 0x1u64], 30);
+        let pairs = [
+            (&zero, &zero),
+            (&one, &max),
+            (&max, &one),
+            (&max, &max),
+            (&mixed, &one),
+        ];
+        let mut paths = vec![];
+        for (x, y) in pairs {
+            trace::take();
+            let _ = x.ct_eq(y);
+            let _ = x.ct_lt(y);
+            let _ = x.ct_gt(y);
+            let _ = BigUInt4096::conditional_select(x, y, x.ct_eq(y));
+            let (mut a, mut b) = (x.clone(), y.clone());
+            BigUInt4096::conditional_swap(&mut a, &mut b, x.ct_lt(y));
+            a.conditional_assign(&b, x.ct_gt(y));
+            let _ = x.ct_add(y);
+            let _ = x.ct_sub(y);
+            let _ = x.ct_mul(y);
+            paths.push(trace::take());
+        }
+        assert!(paths[0].0 > 0);
+        assert!(paths.iter().all(|path| *path == paths[0]));
+    }
+}
@@ -0,0 +1,527 @@
+//! Finite-field Diffie-Hellman over the RFC 3526 MODP groups, all with generator 2.
+//!
+//! Private exponents are uniform in `[2, p - 2]` and exponentiation with them is constant
+//! time. Peer public values are checked to lie in `[2, p - 2]`, which rules out the
+//! degenerate values 0, 1 and `p - 1`.
+
+use crate::random::{CryptoRng, RngCore};
+use crate::{BigUInt, BigUInt4096};
+use std::fmt;
+
+/// zero-extends little-endian chunks to a `BigUInt4096`, for the group constants
+const fn pad(chunks: &[u64]) -> BigUInt4096 {
+    let mut padded = [0u64; 64];
+    let mut i = 0;
+    while i < chunks.len() {
+        padded[i] = chunks[i];
+        i += 1;
+    }
+    BigUInt::from_chunks(padded)
+}
+
+/// RFC 3526 2048-bit MODP prime, `2^2048 - 2^1984 - 1 + 2^64 * (floor(2^1918 * pi) + 124476)`
+pub const MODP_2048_PRIME: BigUInt4096 = pad(&[
+    0xffffffffffffffff,
+    0x15728e5a8aacaa68,
+    0x15d2261898fa0510,
+    0x3995497cea956ae5,
+    0xde2bcbf695581718,
+    0xb5c55df06f4c52c9,
+    0x9b2783a2ec07a28f,
+    0xe39e772c180e8603,
+    0x32905e462e36ce3b,
+    0xf1746c08ca18217c,
+    0x670c354e4abc9804,
+    0x9ed529077096966d,
+    0x1c62f356208552bb,
+    0x83655d23dca3ad96,
+    0x69163fa8fd24cf5f,
+    0x98da48361c55d39a,
+    0xc2007cb8a163bf05,
+    0x49286651ece45b3d,
+    0xae9f24117c4b1fe6,
+    0xee386bfb5a899fa5,
+    0x0bff5cb6f406b7ed,
+    0xf44c42e9a637ed6b,
+    0xe485b576625e7ec6,
+    0x4fe1356d6d51c245,
+    0x302b0a6df25f1437,
+    0xef9519b3cd3a431b,
+    0x514a08798e3404dd,
+    0x020bbea63b139b22,
+    0x29024e088a67cc74,
+    0xc4c6628b80dc1cd1,
+    0xc90fdaa22168c234,
+    0xffffffffffffffff,
+]);
+
+/// RFC 3526 3072-bit MODP prime, `2^3072 - 2^3008 - 1 + 2^64 * (floor(2^2942 * pi) + 1690314)`
+pub const MODP_3072_PRIME: BigUInt4096 = pad(&[
+    0xffffffffffffffff,
+    0x4b82d120a93ad2ca,
+    0x43db5bfce0fd108e,
+    0x08e24fa074e5ab31,
+    0x770988c0bad946e2,
+    0xbbe117577a615d6c,
+    0x521f2b18177b200c,
+    0xd87602733ec86a64,
+    0xf12ffa06d98a0864,
+    0xcee3d2261ad2ee6b,
+    0x1e8c94e04a25619d,
+    0xabf5ae8cdb0933d7,
+    0xb3970f85a6e1e4c7,
+    0x8aea71575d060c7d,
+    0xecfb850458dbef0a,
+    0xa85521abdf1cba64,
+    0xad33170d04507a33,
+    0x15728e5a8aaac42d,
+    0x15d2261898fa0510,
+    0x3995497cea956ae5,
+    0xde2bcbf695581718,
+    0xb5c55df06f4c52c9,
+    0x9b2783a2ec07a28f,
+    0xe39e772c180e8603,
+    0x32905e462e36ce3b,
+    0xf1746c08ca18217c,
+    0x670c354e4abc9804,
+    0x9ed529077096966d,
+    0x1c62f356208552bb,
+    0x83655d23dca3ad96,
+    0x69163fa8fd24cf5f,
+    0x98da48361c55d39a,
+    0xc2007cb8a163bf05,
+    0x49286651ece45b3d,
+    0xae9f24117c4b1fe6,
+    0xee386bfb5a899fa5,
+    0x0bff5cb6f406b7ed,
+    0xf44c42e9a637ed6b,
+    0xe485b576625e7ec6,
+    0x4fe1356d6d51c245,
+    0x302b0a6df25f1437,
+    0xef9519b3cd3a431b,
+    0x514a08798e3404dd,
+    0x020bbea63b139b22,
+    0x29024e088a67cc74,
+    0xc4c6628b80dc1cd1,
+    0xc90fdaa22168c234,
+    0xffffffffffffffff,
+]);
+
+/// RFC 3526 4096-bit MODP prime, `2^4096 - 2^4032 - 1 + 2^64 * (floor(2^3966 * pi) + 240904)`
+pub const MODP_4096_PRIME: BigUInt4096 = pad(&[
+    0xffffffffffffffff,
+    0x4df435c934063199,
+    0x86ffb7dc90a6c08f,
+    0x93b4ea988d8fddc1,
+    0xd0069127d5b05aa9,
+    0xb81bdd762170481c,
+    0x1f612970cee2d7af,
+    0x233ba186515be7ed,
+    0x99b2964fa090c3a2,
+    0x287c59474e6bc05d,
+    0x2e8efc141fbecaa6,
+    0xdbbbc2db04de8ef9,
+    0x2583e9ca2ad44ce8,
+    0x1a946834b6150bda,
+    0x99c327186af4e23c,
+    0x88719a10bdba5b26,
+    0x1a723c12a787e6d7,
+    0x4b82d120a9210801,
+    0x43db5bfce0fd108e,
+    0x08e24fa074e5ab31,
+    0x770988c0bad946e2,
+    0xbbe117577a615d6c,
+    0x521f2b18177b200c,
+    0xd87602733ec86a64,
+    0xf12ffa06d98a0864,
+    0xcee3d2261ad2ee6b,
+    0x1e8c94e04a25619d,
+    0xabf5ae8cdb0933d7,
+    0xb3970f85a6e1e4c7,
+    0x8aea71575d060c7d,
+    0xecfb850458dbef0a,
+    0xa85521abdf1cba64,
+    0xad33170d04507a33,
+    0x15728e5a8aaac42d,
+    0x15d2261898fa0510,
+    0x3995497cea956ae5,
+    0xde2bcbf695581718,
+    0xb5c55df06f4c52c9,
+    0x9b2783a2ec07a28f,
+    0xe39e772c180e8603,
+    0x32905e462e36ce3b,
+    0xf1746c08ca18217c,
+    0x670c354e4abc9804,
+    0x9ed529077096966d,
+    0x1c62f356208552bb,
+    0x83655d23dca3ad96,
+    0x69163fa8fd24cf5f,
+    0x98da48361c55d39a,
+    0xc2007cb8a163bf05,
+    0x49286651ece45b3d,
+    0xae9f24117c4b1fe6,
+    0xee386bfb5a899fa5,
+    0x0bff5cb6f406b7ed,
+    0xf44c42e9a637ed6b,
+    0xe485b576625e7ec6,
+    0x4fe1356d6d51c245,
+    0x302b0a6df25f1437,
+    0xef9519b3cd3a431b,
+    0x514a08798e3404dd,
+    0x020bbea63b139b22,
+    0x29024e088a67cc74,
+    0xc4c6628b80dc1cd1,
+    0xc90fdaa22168c234,
+    0xffffffffffffffff,
+]);
+
+/// errors from Diffie-Hellman key agreement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DhError {
+    /// private exponent outside `[2, p - 2]`
+    InvalidPrivateKey,
+    /// peer public value outside `[2, p - 2]`
+    InvalidPublicKey,
+}
+
+impl fmt::Display for DhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DhError::InvalidPrivateKey => write!(f, "Diffie-Hellman private key out of range"),
+            DhError::InvalidPublicKey => write!(f, "Diffie-Hellman public value out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DhError {}
+
+/// RFC 3526 MODP groups
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModpGroup {
+    /// group 14
+    Modp2048,
+    /// group 15
+    Modp3072,
+    /// group 16
+    Modp4096,
+}
+
+impl ModpGroup {
+    /// prime modulus of the group
+    pub fn prime(&self) -> &'static BigUInt4096 {
+        match self {
+            ModpGroup::Modp2048 => &MODP_2048_PRIME,
+            ModpGroup::Modp3072 => &MODP_3072_PRIME,
+            ModpGroup::Modp4096 => &MODP_4096_PRIME,
+        }
+    }
+
+    /// generator of the group, 2 for every MODP group
+    pub fn generator(&self) -> BigUInt4096 {
+        BigUInt4096::new(&[2u64])
+    }
+
+    /// length of the prime in bytes, which is also the length of encoded public values and
+    /// shared secrets
+    pub fn size(&self) -> usize {
+        self.prime().bits() as usize / 8
+    }
+
+    /// whether `value` lies in `[2, p - 2]`
+    fn in_range(&self, value: &BigUInt4096) -> bool {
+        let two = BigUInt4096::new(&[2u64]);
+        value >= &two && value <= &(self.prime() - &two)
+    }
+}
+
+/// Diffie-Hellman key pair in one of the MODP groups
+#[derive(Clone)]
+pub struct DhKeyPair {
+    group: ModpGroup,
+    private: BigUInt4096,
+    public: BigUInt4096,
+}
+
+// leaves out the private exponent, so that it does not end up in logs
+impl fmt::Debug for DhKeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DhKeyPair")
+            .field("group", &self.group)
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DhKeyPair {
+    /// generates a key pair with a private exponent drawn uniformly from `[2, p - 2]`
+    pub fn generate(group: ModpGroup, rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        let two = BigUInt4096::new(&[2u64]);
+        let bound = group.prime() - BigUInt4096::new(&[3u64]);
+        let private = BigUInt4096::random_below(&bound, rng) + two;
+        Self::from_private(group, private).unwrap()
+    }
+
+    /// builds the key pair for a given private exponent, which must lie in `[2, p - 2]`
+    pub fn from_private(group: ModpGroup, private: BigUInt4096) -> Result<Self, DhError> {
+        if !group.in_range(&private) {
+            return Err(DhError::InvalidPrivateKey);
+        }
+        let public = group.generator().ct_modpow(&private, group.prime());
+        Ok(DhKeyPair {
+            group,
+            private,
+            public,
+        })
+    }
+
+    /// group the key pair belongs to
+    pub fn group(&self) -> ModpGroup {
+        self.group
+    }
+
+    /// public value `g^x mod p`
+    pub fn public_key(&self) -> &BigUInt4096 {
+        &self.public
+    }
+
+    /// shared secret `peer^x mod p` as a big-endian byte string of the group's length,
+    /// after checking that the peer's public value lies in `[2, p - 2]`
+    pub fn shared_secret(&self, peer: &BigUInt4096) -> Result<Vec<u8>, DhError> {
+        if !self.group.in_range(peer) {
+            return Err(DhError::InvalidPublicKey);
+        }
+        let secret = peer.ct_modpow(&self.private, self.group.prime());
+        let bytes = secret.to_be_bytes();
+        Ok(bytes[bytes.len() - self.group.size()..].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DhError, DhKeyPair, ModpGroup};
+    use crate::random::TestRng;
+    use crate::BigUInt4096;
+
+    fn from_hex(hex: &str) -> BigUInt4096 {
+        BigUInt4096::from_str_radix(hex, 16).unwrap()
+    }
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // test the group constants against the first and last words printed in RFC 3526
+    #[test]
+    fn modp_constants_test() {
+        let groups = [
+            (ModpGroup::Modp2048, "15728e5a8aacaa68ffffffffffffffff"),
+            (ModpGroup::Modp3072, "4b82d120a93ad2caffffffffffffffff"),
+            (ModpGroup::Modp4096, "4df435c934063199ffffffffffffffff"),
+        ];
+        for (group, tail) in groups {
+            let hex = group.prime().to_str_radix(16);
+            assert_eq!(hex.len(), 2 * group.size());
+            assert!(hex.starts_with("ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd1"));
+            assert!(hex.ends_with(tail));
+        }
+        assert_eq!(ModpGroup::Modp3072.size(), 384);
+    }
+
+    // test the 2048-bit group against a known answer
+    #[test]
+    fn modp_2048_kat_test() {
+        let pair = DhKeyPair::from_private(
+            ModpGroup::Modp2048,
+            from_hex("4055381bcd954ad94fc0379d3924298891bd1d8ffd81b190e33438bc41a3f4e8"),
+        )
+        .unwrap();
+        let expected_public = from_hex(concat!(
+            "719cb28d372023be4fe6ff60ce7785f2ea7a9453e59626343f094c39e4c5bba2811130025344aa57",
+            "aa6a9199fc5c1770d50463b24e4818ba926f5a431812729cc9c2447ac66cf2104e126a1486843449",
+            "88da4a9f38c5d7697ae0db2c44ce2672f30ba32c51faef9eadee474cac49c6b1d4a0514bb8ef0f98",
+            "e57f48d063997d25c4cdbbaf10b5db25767c727ad0055d79b353350d4d2987d3517b4a7c724b65f3",
+            "f425e64b52b1badfe9a2fead27be6b011b91cf3789a908ad579faf92be52a8b2344a25bc7855f177",
+            "b1a6ff624cacb12e2a134cb0cde3c71df6573c1308185c429340b057afbe2874aab40116227af03f",
+            "b53af78933898d3cced810956cabf30d",
+        ));
+        assert_eq!(pair.public_key(), &expected_public);
+        let peer = from_hex(concat!(
+            "61cc552f2deb1575e497bc0527ce0716d67ac313cfbe9a31dec6360a0e6b94ded61156452fbe9249",
+            "873d20c3785ab62366be560ca53c80ead442cd6d60a2e86bee42856167acf38c156054b53ada4e47",
+            "b9e2f8835a068613167c129557058c9323ca320abad05ae5a8c7103abbdf8d3853795a754f5eac90",
+            "b37116672666c7387d05b13581d267d825e1cadd62be9343f08612182f14ba4778df6d86e331809e",
+            "e1f8662b122d80cbed4aefcd41428144daee8b04d393e55e2ae191dd765650d88e09142e25784135",
+            "8436168b897890d1465b010c2aa7e9d231bec6cc51c86f4c51367e59410586a616d59679f359508d",
+            "d2debede74bd082a819f687b91e9dada",
+        ));
+        let expected_secret = concat!(
+            "c2fa5893bab0821ee4e473202db2fb884ddcae439a90d24cfc7ebc2a14245e338d6f432e8708da14",
+            "86436d7423b4433ab97d14da55d1d066c6f0365dda11fd6509fec6c12622b2e4954bc84eccfffd2f",
+            "80b5fb23e79109e6c31088c72bcdf2fc3eaf5c39354f4dd6359640b441cfbe9683ebdfcdc02fbc51",
+            "4a9214a2a73679cf755d72e172b39c46d4b2eed74eff7ea547be2cc229f039c01145476e945f4d40",
+            "8b4fe48748cdd390081a9e385be3ed6efb31a99dcc0d0d97dbbbf93452c161faf1e264e5cb2c4383",
+            "df8a3020b9b073f82b51bc2c860b220b345239cf5ec74cf9fa77308a9e380e6119358487643fcd89",
+            "119a31bcbe977596da00014857a3faa9",
+        );
+        assert_eq!(pair.shared_secret(&peer).unwrap(), bytes(expected_secret));
+    }
+
+    // test the 3072-bit group against a known answer
+    #[test]
+    fn modp_3072_kat_test() {
+        let pair = DhKeyPair::from_private(
+            ModpGroup::Modp3072,
+            from_hex("809ac5aacc36decab425fc2b2637f3e3e65976d335dc373f874a9d6c22cfaad8"),
+        )
+        .unwrap();
+        let expected_public = from_hex(concat!(
+            "bf6f2efe784c39f63575799f4f896923b2c808d6b0057b842410849f6cb09e55e78780ff7ffcdf83",
+            "a78f1ef3e72dd661e24a5cb4da6df2b12907ddc2ae9da92a5f33ddf55b3dd0e14480b7586d2d9c50",
+            "c7724202036cde2e2fc42e0d44ea186cfcd122417d9deee9c40f52299b2a94c06e959dae9f554a81",
+            "072980f0fe124387df224e901a7ae64ab9819482bb7df7922cccb33b31ae54cc613933b9ecfe3f2f",
+            "522cd9c8e332b7c490dbc9468f8606375d6557e12576a45aa946f03f1a3ec4d2d14f86a5a3c9bfc2",
+            "c6a9b7b0ccc8086f8cf00c0a8c0305be8d77486579bc6b62ecbac18543b0ddd90297490263619eb6",
+            "d57365b312e86de7f3819ba1b6ff40672c11660da4f84369bc201c556a403b12fe6464c4dd435431",
+            "f22c9520995d52c9e94201c32df800590fda7e44bc1dd8579e79a0c7f780e91a69be6c65facc1d2b",
+            "4e210f89e387a07e74f8705d2d9b61a1c2d2c7dcbae4b026360d2d27319ebd7a1c87669fa0614620",
+            "42deef7e935fb27222e2e7cd31c0d2841fc48009a0658d8e",
+        ));
+        assert_eq!(pair.public_key(), &expected_public);
+        let peer = from_hex(concat!(
+            "b242b18cc0e616e89505e284a147ef29177bd296fa7864405e5f4dad904f1b19ca0cf0ee051ab910",
+            "47b56b98184aa318380d53a065ba23888a4e2901d2308f10721b76bc2b2be322499bfbde4b33fa45",
+            "68f485f96092b93076ee4a8855baa00d73fc6a71c72227a4eb5f08eb33c7bdc29cbddf6e54c2797f",
+            "e94cb09140afdb13d763a5c0f7c08f7f0cbf12a3f754f785b16c616f33eb02660cf7976582312c0e",
+            "bb53b9f764f782b36d24661de8d0ae79b2c20c852cf880aef7acc3b70441ddf45be054911dbe5477",
+            "e130474c6b0540770029bf99bdc6b63b2cfc5198c28ab655f6dd6c7c994b8449e4171160079cef81",
+            "283e42d20f7a134273558df57f85dd295964f53c687f01e6c87f8252a38c82b72eb0e4bc0ffce9d5",
+            "8fe8aca336099d9605de5e4fdda22be5839902e09a19e831f0b16cdf4842d87f6c95034065c321eb",
+            "124cab56f8d925a642cb87bb13238bf5c89145942e8029dfecec081203095b8ae3c0df2b282cf391",
+            "667a432155ca5a5248c89deb8d9d362ee1f10f96c0dc8683",
+        ));
+        let expected_secret = concat!(
+            "c4a8bd89a3ece82e48becfaed09ea226cf36d12d46d6599aea2769f09b15f501e851ff1edd5b5cad",
+            "028e1e05e49e9146222ed9a160d93d558f1f55a68ddb7725487bc794491f5f57cf8b647ee44176e0",
+            "c99741dfeed2800b30542bb5f3e073dd2a2d201551e3e96d8fe3f450d092becfa80196680fce5b39",
+            "1deefc383deb61e7548d397d76cf673d1946595b4f81dbf0d9d1fdef159c79ee5cc7627ca2c61d0c",
+            "fee22078c39a697e0cb05b96cbe97cf3475e87cb2653a68dffb66ec003667d888f01e47d0f36dfe3",
+            "c5505c57f897c938079a2d14da4cfb1e13afc13a185144f33d69412e16f5ebf871ae8f4ffb70453d",
+            "70fbe5c1cb45d0632e1f825aa4a3b820a4a9c392e3392d12335050b77983261cf75e9daa108940d1",
+            "19a93198a49e28cd55e92e64eefb12aeac4af633e101afc26d28bc68a0a007c11c34955e2ba93b77",
+            "1404807242b53a24536d75fa78598356e1d5721e05d7f02439b3adb32eb797aa6600155075ad4b3d",
+            "5fb8a5eedbf6cc76829b958762b1885c3efb229f52cfeef8",
+        );
+        assert_eq!(pair.shared_secret(&peer).unwrap(), bytes(expected_secret));
+    }
+
+    // test the 4096-bit group against a known answer
+    #[test]
+    fn modp_4096_kat_test() {
+        let pair = DhKeyPair::from_private(
+            ModpGroup::Modp4096,
+            from_hex("ff18429bb965d83deb1f2aa7b5e09126652281f5387e78d1e264b9313d25d7be"),
+        )
+        .unwrap();
+        let expected_public = from_hex(concat!(
+            "626170aa51c79f2e52bb90efa4403b35b65419006e62b25abda516f6f1635174e6bad73c44bd2b2e",
+            "e225e239dfcb6505308863361e8815ac903122a7b9c5819941344c9cf6ff47ee4b6d1d0154245ec2",
+            "74c8d75579b6ea29e6f42c140c817992936c0c71057454c9c40232da1689c9860962db8dec48a6ac",
+            "f676a8298b1f22c41c3638aa9ffb5b7d012290c2f74409c9f0430c49a48ea6fcbc87a55e13a0ef95",
+            "248eac18fe7e69f0bd69bb69d6fa0a8a17705ebe269d510a4ff17484f28fb1c0448f14dc6d603978",
+            "a5d140f7496854c25ee2f47cc9e05c6f0e4c2fc757dce82f9fd9588964fe6730a757021006b2af1d",
+            "778055ed13e98aa64ad3ebb17463c756fc6f4a7767e34a435b07bd86243a013bbef6b1da0c8c42d7",
+            "3d688d8fa2e01bf9328cfc9cf30f3382b8162b2a03a7506f5318c10949c14f9c5f833a0f876981c1",
+            "ad0563322ee735e12d9034fdc5939877d8ea9c4b3f9732d4f7e364021c81dfd0322693931d93b4a2",
+            "b7d1b6d455d57abbbc6fc7b4208db2bbbd68e922a228ff94b88d2139f62e34eb63f25bb072fb5b89",
+            "457b4709ab5c89fa0c544e64f23888dfb42f0a5e68eb76f099f2c036690810fb4fc808c36ab484db",
+            "5640c2d75e255cebac6ffeccff1f838dbcc3ad8e2d6850b8ad5ff493e75325a3fd652799b324d337",
+            "3c0ff55eff0ac7959b599818083d07651d2d1b938e3290bfdb32896830bb9aaf",
+        ));
+        assert_eq!(pair.public_key(), &expected_public);
+        let peer = from_hex(concat!(
+            "3f14c095deb3121774763fdc95467fca5840b5eb6c249f4e5100906bc83655a33ab8ea35561c2878",
+            "8fac01a4efd6fdf66cf8b8135e685f50d91d517f2484e05b0d5fb5c79bd6d9f260edd6f4f7bc7021",
+            "ba441f888b7f9c393cfbc39a279f96e225e185333582e9cd3d607d7cc61a0bfd0e5bd85bde6d5f64",
+            "0288b7deddc83ebc708e486c8f3ddc0f77d365eec1d60a0fe6b14786424fdd65d22576b430cd919d",
+            "511758868e17d86205ebb4d1441c5b7cbd82ac7ea3f7ab5eac17c580453ff7f0caf0ed9b5d8e84aa",
+            "3710ad302daf41b9e66421ec558e3a12a240a7a31cc20e7868828df698ae8e39c8f40d79f95dddb4",
+            "9837dfa3b24639914e4874fd8df17d59243dcb4880895a8c02d97613a6c21923e064cbb37deb4150",
+            "5e41f65ef1ebd0e5cb9f09d144acf28633760fc0d3b5edaa2e7a49d8ab19caf28f8bd813959d1048",
+            "f5b8e53240e38e40c4b0b70345e9b0bc7450e2dfcac2ec110389540091bf273872a40cd6e7196dc8",
+            "6dbe938e0fe81180714707c1ef0f9bef2797696b41de738517045920a7c3eed27fe91340ad5b6994",
+            "1ba8333ed43019061cd043099e209841d027f902a7e1bb30cd794a4032800471a3694824301efb77",
+            "49e61db3099547b0c7eede998d5436e0fba221760aa13153bd8ff798e0c2c37fa110554eb5ce732e",
+            "d8d1e9552347346b507fecdf1cc5e56535d6bb2e3c576c0b3d3d5b4039cf18cc",
+        ));
+        let expected_secret = concat!(
+            "8994436bec63ff0b413840777a630451bd7070a57e53b835fe334da9fb8c3ef83b3971f416f4be96",
+            "66d21f5468dd110c61e4a52f7756e581f26bef51825a379d76310b815faa22e3faa55266b47038bb",
+            "8dbc5f9a5a29781765fdf9b50e783af7ed180710d7d59eeb4d9f70664525e7401e791edf05eaf790",
+            "7a2407955eff88ebebd1914384ae8e8d705108b854680424c6071c64cb663c260a049e8cea42c85f",
+            "b100d614278e2a33d6b8796c761a90c77cbebea268154adedd49af4b4fa5130ba1427a0a7d97e8b7",
+            "49b8e55c04cb77cfa293501681f7c833cf00b2e58e850e2dabcc2d254acf492fa2c5f262720d5c7d",
+            "9d26e490c39eb6a569d151f63b9f6f0c1fadc21038221b14b092b36fe1b4c3e1416568a08a67a6f4",
+            "d2229a1c3e94577982fe536d977a1af3b41da39b6246651567d42192cabe3ac5222c738f611114d8",
+            "1e1b0d2045cc91e7c81106c465b77989e54b9012cbf62a4561d6210690f088fd2ee6bc27f093d25b",
+            "6bb71f9c38fea9a6974ca4da274b4f41bd494a1414f5c3a2c31ff3fa6a26d003ba2eaf24d2ddaef0",
+            "46b20d8dadfad2ff4df042cc98e5f18eab9584f4c4353053a976cc6a42f6707b013043dec4b41758",
+            "71c31f8ee14d875514d98ad4cb1ecd85ee6a4152eb58d231905873331de5d2d82fcfa7fff0c20c4a",
+            "37c76146524049d8097d5917951023f6b62799d0c5eac98a37cb3ddfc7328b7d",
+        );
+        assert_eq!(pair.shared_secret(&peer).unwrap(), bytes(expected_secret));
+    }
+
+    // test two generated key pairs agree on the shared secret
+    #[test]
+    fn agreement_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let alice = DhKeyPair::generate(ModpGroup::Modp2048, &mut rng);
+        let bob = DhKeyPair::generate(ModpGroup::Modp2048, &mut rng);
+        assert_ne!(alice.public_key(), bob.public_key());
+        let secret = alice.shared_secret(bob.public_key()).unwrap();
+        assert_eq!(secret.len(), 256);
+        assert_eq!(bob.shared_secret(alice.public_key()).unwrap(), secret);
+    }
+
+    // test out-of-range public values and private exponents are rejected
+    #[test]
+    fn range_test() {
+        let group = ModpGroup::Modp2048;
+        let p = group.prime();
+        let one = BigUInt4096::new(&[1u64]);
+        let pair = DhKeyPair::from_private(group, BigUInt4096::new(&[2u64])).unwrap();
+        assert_eq!(pair.public_key(), &BigUInt4096::new(&[4u64]));
+        for peer in [
+            BigUInt4096::default(),
+            one.clone(),
+            p - &one,
+            p.clone(),
+            p + &one,
+        ] {
+            assert_eq!(pair.shared_secret(&peer), Err(DhError::InvalidPublicKey));
+            assert_eq!(
+                DhKeyPair::from_private(group, peer).err(),
+                Some(DhError::InvalidPrivateKey)
+            );
+        }
+        // values from a larger group are out of range for a smaller one
+        assert_eq!(
+            pair.shared_secret(&(ModpGroup::Modp3072.prime() - &one - &one)),
+            Err(DhError::InvalidPublicKey)
+        );
+        assert!(pair.shared_secret(&(p - &one - &one)).is_ok());
+    }
+
+    // test that debug output leaves out the private exponent
+    #[test]
+    fn debug_test() {
+        let pair = DhKeyPair::from_private(ModpGroup::Modp2048, BigUInt4096::new(&[3u64])).unwrap();
+        let debug = format!("{:?}", pair);
+        assert!(debug.starts_with("DhKeyPair { group: Modp2048, public: BigUInt { chunks: [8, "));
+        assert!(debug.ends_with(".. }"));
+        assert_eq!(debug.matches("chunks").count(), 1);
+    }
+}
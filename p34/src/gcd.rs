@@ -0,0 +1,403 @@
+use crate::{BigInt, BigUInt};
+
+/// result of `extended_gcd`: `gcd = x * self + y * other`, with signed Bezout coefficients
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtendedGcd<const LIMBS: usize> {
+    pub gcd: BigUInt<LIMBS>,
+    pub x: BigInt<LIMBS>,
+    pub y: BigInt<LIMBS>,
+}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// greatest common divisor by Stein's binary algorithm; `gcd(0, 0)` is zero
+    pub fn gcd(&self, other: &Self) -> Self {
+        if self.is_zero() {
+            return other.clone();
+        }
+        if other.is_zero() {
+            return self.clone();
+        }
+        // pull out the common power of two, then keep both values odd
+        let shift = self.trailing_zeros().min(other.trailing_zeros());
+        let mut u = self >> self.trailing_zeros();
+        let mut v = other >> other.trailing_zeros();
+        while !v.is_zero() {
+            v >>= v.trailing_zeros();
+            if u > v {
+                std::mem::swap(&mut u, &mut v);
+            }
+            v -= &u;
+        }
+        u << shift
+    }
+
+    /// least common multiple; zero if either value is zero
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::default();
+        }
+        (self / self.gcd(other)) * other
+    }
+
+    /// halves `self` modulo the odd `modulus`, for `self < modulus`
+    fn half_mod(&mut self, modulus: &Self) {
+        if self.bit(0) {
+            let carry;
+            (*self, carry) = self.overflowing_add(modulus);
+            *self >>= 1;
+            self.set_bit(64 * LIMBS as u32 - 1, carry);
+        } else {
+            *self >>= 1;
+        }
+    }
+
+    /// `self - other` modulo `modulus`, for `self, other < modulus`
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        match self.overflowing_sub(other) {
+            (diff, true) => diff.wrapping_add(modulus),
+            (diff, _) => diff,
+        }
+    }
+
+    /// extended binary GCD of `self` and the odd `other`, both non-zero: returns the gcd and
+    /// a coefficient `x` in `[0, other)` with `x * self` congruent to the gcd modulo `other`
+    fn half_extended_gcd(&self, other: &Self) -> (Self, Self) {
+        // invariants: x1 * self = u and x2 * self = v, modulo other
+        let mut u = self.clone();
+        let mut v = other.clone();
+        let mut x1 = Self::new(&[1u64]) % other;
+        let mut x2 = Self::default();
+        while !u.is_zero() {
+            while !u.bit(0) {
+                u >>= 1;
+                x1.half_mod(other);
+            }
+            while !v.bit(0) {
+                v >>= 1;
+                x2.half_mod(other);
+            }
+            if u >= v {
+                u -= &v;
+                x1 = x1.sub_mod(&x2, other);
+            } else {
+                v -= &u;
+                x2 = x2.sub_mod(&x1, other);
+            }
+        }
+        (v, x2)
+    }
+
+    /// extended Euclidean algorithm, by the binary method: returns the gcd and signed
+    /// coefficients `x`, `y` with `gcd = x * self + y * other`
+    pub fn extended_gcd(&self, other: &Self) -> ExtendedGcd<LIMBS> {
+        if self.is_zero() || other.is_zero() {
+            // gcd(a, 0) = 1 * a and gcd(0, b) = 1 * b
+            let one = Self::new(&[1u64]);
+            let (x, y) = if self.is_zero() {
+                (Self::default(), one)
+            } else {
+                (one, Self::default())
+            };
+            return ExtendedGcd {
+                gcd: self | other,
+                x: x.into(),
+                y: y.into(),
+            };
+        }
+        // remove the common power of two; the coefficients do not change
+        let shift = self.trailing_zeros().min(other.trailing_zeros());
+        let a = self >> shift;
+        let b = other >> shift;
+        // the halving steps need an odd modulus, so work modulo whichever value is odd
+        let swapped = !b.bit(0);
+        let (a, b) = if swapped { (b, a) } else { (a, b) };
+        let (gcd, x) = a.half_extended_gcd(&b);
+        // y = (gcd - x * a) / b, which divides exactly; x * a >= gcd unless x is zero
+        let y = if x.is_zero() {
+            BigInt::from(&gcd / &b)
+        } else {
+            // exact division by the odd b is multiplication by its inverse mod 2^(64 * LIMBS),
+            // found by Newton iteration
+            let mut inv = b.clone();
+            while !b.wrapping_mul(&inv).is_one() {
+                inv = inv.wrapping_mul(&Self::new(&[2u64]).wrapping_sub(&b.wrapping_mul(&inv)));
+            }
+            -BigInt::from(x.wrapping_mul(&a).wrapping_sub(&gcd).wrapping_mul(&inv))
+        };
+        let x = BigInt::from(x);
+        let gcd = gcd << shift;
+        if swapped {
+            ExtendedGcd { gcd, x: y, y: x }
+        } else {
+            ExtendedGcd { gcd, x, y }
+        }
+    }
+
+    /// inverse of `self` modulo `modulus`, or `None` if they are not coprime or the modulus is zero
+    pub fn mod_inverse(&self, modulus: &Self) -> Option<Self> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let value = self % modulus;
+        let ext = value.extended_gcd(modulus);
+        if !ext.gcd.is_one() {
+            return None;
+        }
+        BigUInt::try_from(ext.x.rem_euclid(&BigInt::from(modulus.clone()))).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt4096, BigUInt256, BigUInt4096};
+
+    // checks gcd = x * a + y * b for values small enough that the products do not overflow
+    fn check_bezout(a: &BigUInt4096, b: &BigUInt4096) {
+        let ext = a.extended_gcd(b);
+        assert_eq!(ext.gcd, a.gcd(b));
+        let combination =
+            &ext.x * BigInt4096::from(a.clone()) + &ext.y * BigInt4096::from(b.clone());
+        assert_eq!(combination, BigInt4096::from(ext.gcd));
+        assert!(!(ext.x.is_negative() && ext.y.is_negative()));
+    }
+
+    // test gcd, lcm and extended gcd of small values, including zeros
+    #[test]
+    fn gcd_lcm_test1() {
+        let small = |n: u64| BigUInt4096::new(&[n]);
+        assert_eq!(small(48).gcd(&small(18)), small(6));
+        assert_eq!(small(17).gcd(&small(5)), small(1));
+        assert_eq!(small(0).gcd(&small(7)), small(7));
+        assert_eq!(small(7).gcd(&small(0)), small(7));
+        assert_eq!(small(0).gcd(&small(0)), small(0));
+        assert_eq!(small(4).lcm(&small(6)), small(12));
+        assert_eq!(small(0).lcm(&small(6)), small(0));
+        for (a, b) in [
+            (48, 18),
+            (18, 48),
+            (240, 46),
+            (7, 0),
+            (0, 7),
+            (0, 0),
+            (12, 4),
+            (4, 12),
+            (1, 1),
+        ] {
+            check_bezout(&small(a), &small(b));
+        }
+    }
+
+    // test extended gcd of pseudo-random pairs against the Bezout identity
+    #[test]
+    fn extended_gcd_test() {
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..50 {
+            let a = BigUInt4096::new(&[next(), next(), next() >> (next() % 64)]);
+            let b = BigUInt4096::new(&[next() << (next() % 64), next()]);
+            let common = BigUInt4096::new(&[next() >> 40]);
+            check_bezout(&(&a * &common), &(&b * &common));
+        }
+    }
+
+    // test modular inverse of small values
+    #[test]
+    fn mod_inverse_test1() {
+        let small = |n: u64| BigUInt256::new(&[n]);
+        assert_eq!(small(3).mod_inverse(&small(11)), Some(small(4)));
+        assert_eq!(small(14).mod_inverse(&small(11)), Some(small(4)));
+        assert_eq!(small(3).mod_inverse(&small(8)), Some(small(3)));
+        assert_eq!(small(10).mod_inverse(&small(7)), Some(small(5)));
+        assert_eq!(small(2).mod_inverse(&small(4)), None);
+        assert_eq!(small(0).mod_inverse(&small(5)), None);
+        assert_eq!(small(3).mod_inverse(&small(0)), None);
+        assert_eq!(small(5).mod_inverse(&small(1)), Some(small(0)));
+        // near the top of the range, where halving needs the carry
+        let modulus = !BigUInt256::default();
+        let value = small(2);
+        let inverse = value.mod_inverse(&modulus).unwrap();
+        assert_eq!(inverse, (modulus.clone() >> 1) + small(1));
+    }
+
+    // test gcd and lcm of values sharing a large odd factor and a power of two
+    #[test]
+    fn gcd_lcm_test2() {
+        let a = BigUInt4096::new(&[
+            0x0u64,
+            0xcf34956b1ea49900u64,
+            0x5797cc451ce958f2u64,
+            0x6eab43a3066aac6du64,
+            0x5402ba99a9118a60u64,
+            0x3121843ba9c286e6u64,
+            0x4555671cc3cdd842u64,
+            0x67fa5b360f57be41u64,
+            0x7a93b845b25f96f5u64,
+            0x16e8b3716e9950d3u64,
+            0xa0b79ccae87021c9u64,
+            0x1260220833a1440fu64,
+            0x9f3ed60b3ab406aau64,
+            0x79d62bf969ee294cu64,
+            0x1298bc2a5e704746u64,
+            0x2c38c1a121b1818au64,
+            0x796337836f38c379u64,
+            0xa6e94e8f72c5641u64,
+            0x291b97b99e42202du64,
+            0x5d6b2aa243e25b34u64,
+            0x66bcf31fbd6da44au64,
+            0x515b8b75ed592ff9u64,
+            0x25638b4d853cadf5u64,
+            0x34a7385f2b966a1cu64,
+            0x10c15c3e2eaf7e01u64,
+            0x1bu64,
+        ]);
+        let b = BigUInt4096::new(&[
+            0x3a24a2dd3c58a620u64,
+            0xfd2d0d528965a0aeu64,
+            0x1afb1e977595dc1au64,
+            0x422a9dfb51e826fbu64,
+            0x39ccac0427288ae0u64,
+            0xb52d9202a749a944u64,
+            0x210470e4b23cc6e4u64,
+            0x48f7246171f73702u64,
+            0xded5a511c7e75755u64,
+            0xcf360ece8d4aa463u64,
+            0xb8ac5934af9faa85u64,
+            0xcf16e410bb4635e6u64,
+            0x12721cd8f49374e5u64,
+            0x20f7a7b77263395au64,
+            0xa0a58738556fbb9u64,
+            0x665cce26f676f82du64,
+            0x484dc6fb0542a799u64,
+            0x6bb396072d3dc1e7u64,
+            0x76e9afd8598322f9u64,
+            0x8f71cc90c52533a8u64,
+            0x239e215b40a98b82u64,
+            0x9a8619ec86dfbea7u64,
+            0x44011a8dffccb30fu64,
+            0x3b310abf7b9d4cadu64,
+            0x1u64,
+        ]);
+        let expected = BigUInt4096::new(&[
+            0x9b9b4d4f2faedbe0u64,
+            0xeea3a9950a03c588u64,
+            0x31711b75533c0f30u64,
+            0x36673fe4902e9cabu64,
+            0xe4544f6058f7fe4cu64,
+            0x70f53c4bfdfd223fu64,
+            0x96cdf188c5009b6fu64,
+            0xba1bacd9ee5f0b14u64,
+            0xeu64,
+        ]);
+        assert_eq!(a.gcd(&b), expected);
+        assert_eq!(b.gcd(&a), expected);
+        assert_eq!(a.lcm(&b), &a / &expected * &b);
+        check_bezout(&a, &b);
+        check_bezout(&b, &a);
+    }
+
+    // test modular inverse modulo a large even modulus
+    #[test]
+    fn mod_inverse_test2() {
+        let value = BigUInt4096::new(&[
+            0xa3ef19e805c2f63u64,
+            0x9e7bd62f3f5fcff5u64,
+            0xfbdb4de81db6027cu64,
+            0xff9d608a5c675c72u64,
+            0x80ebe5578beb81b1u64,
+            0x61eefe027a16894u64,
+            0x528c8100ed711abau64,
+            0xc9932fb6e1c88247u64,
+            0xd4faf4b1ab288d38u64,
+            0xa3b0718f47896893u64,
+            0x3ac171b11e3c492cu64,
+            0x86f90255c8bfb0f0u64,
+            0x7a3621f580904b4cu64,
+            0xa0835b6b7d601939u64,
+            0x433ededd5a8e7f31u64,
+            0xf2597377af3d6c6bu64,
+            0xe4f20d3471e07116u64,
+            0x29828207bd230058u64,
+            0x4a9409b172e45d1du64,
+            0xdf880b119dec1a32u64,
+            0x473effcf0bc66a8au64,
+            0xf1a3ab9f547af48du64,
+            0x83e2500bdcb6be69u64,
+            0xa3e636fu64,
+        ]);
+        let modulus = BigUInt4096::new(&[
+            0x9b811f47668864beu64,
+            0xab0bcefa6b391ca9u64,
+            0x78370ed498918dd8u64,
+            0x9bfad94f7a0d7bdau64,
+            0x8a88c0676273ed06u64,
+            0xa406bf0c07ce7adeu64,
+            0x1529755db9f09825u64,
+            0x311e281cf7ab62a8u64,
+            0xc98cd9dff9ef0b3cu64,
+            0x42f5d75ea9e16e27u64,
+            0xf0eb21aa5b397037u64,
+            0xddbad0b15cf5fe24u64,
+            0x62b64cfeb0ab577au64,
+            0x4f55c73dac7c603bu64,
+            0x409bd3051d241ed6u64,
+            0x55d1ce913c272728u64,
+            0x5f0b4a7f5d02b200u64,
+            0x92bf2f7382e7ddc9u64,
+            0xc3dc69fccf632d49u64,
+            0x2dedb6a78000cb60u64,
+            0x6183a9eb073465b8u64,
+            0xdbece4ead293ca94u64,
+            0x85ffef86e1e98e2u64,
+            0x696f56184dd01fau64,
+            0xacf7af1538dfffb7u64,
+            0xb21bbba6d5fcd18u64,
+            0x3453e71c63641911u64,
+            0x9b774054c59c74abu64,
+            0xc0d7fcc51a44db6eu64,
+            0x387be8b88ccaec71u64,
+            0x13f68ff12d94a778u64,
+            0xc616e73db22635cbu64,
+        ]);
+        let expected = BigUInt4096::new(&[
+            0xf155b88eb0886523u64,
+            0xe3395515b5472d6bu64,
+            0x1b4e7ea6608f78c5u64,
+            0x2b7033f003a9f502u64,
+            0xa97c859748d61f8fu64,
+            0xc93a60d0eaa24604u64,
+            0xa03d3e997886b1edu64,
+            0x4a3d49232b21de69u64,
+            0xb03c5d7087bcb926u64,
+            0x2d37f01b7a7e943cu64,
+            0xdac72f6bd3c64f53u64,
+            0xccba1daf08eb7bc4u64,
+            0xd308190b15ea01a9u64,
+            0x1284e55f71f0faf9u64,
+            0x7b10e89fade17f3au64,
+            0x7042eeb43bd06a9eu64,
+            0x550acfe3bb3fbf13u64,
+            0x56606f531e458046u64,
+            0x30a31f254d140233u64,
+            0x5682c2460a52facau64,
+            0x99de60fdeef9e7eau64,
+            0x8d2f24a5c0928eeau64,
+            0xfce00f0dae7727d9u64,
+            0xca0deffd7c13b4cu64,
+            0xadf56adeb5d82d15u64,
+            0x646f0cfacd8cd1eau64,
+            0xa1ef5f80356c58feu64,
+            0x2edceddfce4d8884u64,
+            0xe6bb90b384f2e838u64,
+            0x9cbaa5b56564ca6fu64,
+            0xf5ecf1b94c77222au64,
+            0x91f0d0a0c0850408u64,
+        ]);
+        assert_eq!(value.mod_inverse(&modulus), Some(expected));
+        assert_eq!((&value * &modulus).mod_inverse(&modulus), None);
+    }
+}
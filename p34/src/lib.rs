@@ -1,259 +1,919 @@
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct BigUInt4096 {
-    chunks: [u64; 64],
-}
+pub mod bits;
+pub mod bytes;
+pub mod convert;
+pub mod ct;
+pub mod dh;
+pub mod gcd;
+pub mod modular;
+pub mod montgomery;
+#[cfg(feature = "num-traits")]
+mod num;
+pub mod prime;
+pub mod radix;
+pub mod random;
+pub mod roots;
+pub mod rsa;
+pub mod signed;
+
+pub use signed::{BigInt, BigInt1024, BigInt2048, BigInt256, BigInt4096, BigInt512, BigInt8192};
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct BigUInt8192 {
-    chunks: [u64; 128],
+/// fixed-width unsigned integer made of `LIMBS` 64-bit chunks
+#[derive(Clone)]
+pub struct BigUInt<const LIMBS: usize> {
+    chunks: [u64; LIMBS],
+    // every chunk from index `len` up is zero; may overestimate, but never underestimates
+    len: usize,
 }
 
-impl Default for BigUInt4096 {
+pub type BigUInt256 = BigUInt<4>;
+pub type BigUInt512 = BigUInt<8>;
+pub type BigUInt1024 = BigUInt<16>;
+pub type BigUInt2048 = BigUInt<32>;
+pub type BigUInt4096 = BigUInt<64>;
+pub type BigUInt8192 = BigUInt<128>;
+
+impl<const LIMBS: usize> Default for BigUInt<LIMBS> {
     fn default() -> Self {
-        BigUInt4096 { chunks: [0u64; 64] }
+        Self::ZERO
     }
 }
 
-impl Default for BigUInt8192 {
-    fn default() -> Self {
-        Self {
-            chunks: [0u64; 128],
+impl<const LIMBS: usize> PartialEq for BigUInt<LIMBS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.chunks == other.chunks
+    }
+}
+
+impl<const LIMBS: usize> Eq for BigUInt<LIMBS> {}
+
+impl<const LIMBS: usize> std::fmt::Debug for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BigUInt")
+            .field("chunks", &self.chunks)
+            .finish()
+    }
+}
+
+impl<const LIMBS: usize> Ord for BigUInt<LIMBS> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // compare numerically, starting from the most significant chunk that either may use
+        let len = self.len.max(other.len);
+        self.chunks[..len]
+            .iter()
+            .rev()
+            .cmp(other.chunks[..len].iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for BigUInt<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// number of chunks at or below which multiplication falls back from Karatsuba to schoolbook
+///
+/// on an x86-64 laptop (`cargo +nightly bench` in this crate), a 64-chunk product took about 24us
+/// with the original row-by-row schoolbook, about 9us with in-place schoolbook and about 7us with
+/// one level of Karatsuba; recursing further (threshold 8) and splitting 32-chunk operands
+/// were no faster
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// adds `other` into `sum`, which may be longer, returning the carry out of the top chunk
+fn add_chunks(sum: &mut [u64], other: &[u64]) -> bool {
+    let mut carry: bool = false;
+    let mut carry2: bool;
+    // convention: least significant u64 is at index 0
+    for i in 0..other.len() {
+        (sum[i], carry2) = sum[i].overflowing_add(u64::from(carry));
+        (sum[i], carry) = sum[i].overflowing_add(other[i]);
+        carry = carry || carry2;
+    }
+    for chunk in sum[other.len()..].iter_mut() {
+        if !carry {
+            break;
         }
+        (*chunk, carry) = chunk.overflowing_add(1);
     }
+    carry
 }
 
-impl BigUInt8192 {
-    fn overflowing_add(&self, other: &Self) -> (Self, bool) {
-        let mut sum = Self::default();
-        let mut carry: bool = false;
-        let mut carry2: bool;
-        // convention: least significant u64 is at index 0
-        for i in 0..128 {
-            (sum.chunks[i], carry2) = self.chunks[i].overflowing_add(u64::from(carry));
-            (sum.chunks[i], carry) = sum.chunks[i].overflowing_add(other.chunks[i]);
-            carry = carry || carry2;
+/// subtracts `other` from `diff`, which may be longer, returning the borrow out of the top chunk
+fn sub_chunks(diff: &mut [u64], other: &[u64]) -> bool {
+    let mut borrow: bool = false;
+    let mut borrow2: bool;
+    // convention: least significant u64 is at index 0
+    for i in 0..other.len() {
+        (diff[i], borrow2) = diff[i].overflowing_sub(u64::from(borrow));
+        (diff[i], borrow) = diff[i].overflowing_sub(other[i]);
+        borrow = borrow || borrow2;
+    }
+    for chunk in diff[other.len()..].iter_mut() {
+        if !borrow {
+            break;
         }
-        (sum, carry)
+        (*chunk, borrow) = chunk.overflowing_sub(1);
     }
+    borrow
+}
 
-    fn reset(&mut self) {
-        for i in 0..128 {
-            self.chunks[i] = 0;
+/// schoolbook product of `one` and `other` into `prod`, accumulating each row in place
+fn schoolbook_mul_chunks(one: &[u64], other: &[u64], prod: &mut [u64]) {
+    prod.fill(0);
+    for (i, &chunk) in one.iter().enumerate() {
+        if chunk == 0 {
+            continue;
+        }
+        let mut carry: u64 = 0;
+        for (j, &other_chunk) in other.iter().enumerate() {
+            let acc = prod[i + j] as u128 + chunk as u128 * other_chunk as u128 + carry as u128;
+            prod[i + j] = acc as u64;
+            carry = (acc >> 64) as u64;
         }
+        prod[i + other.len()] = carry;
     }
+}
 
-    fn split(&self) -> (BigUInt4096, BigUInt4096) {
-        let mut left = [0u64; 64];
-        let mut right = [0u64; 64];
-        left.clone_from_slice(&self.chunks[..64]);
-        right.clone_from_slice(&self.chunks[64..]);
-        (BigUInt4096 { chunks: left }, BigUInt4096 { chunks: right })
+/// product of the equal-length `one` and `other` into `prod`, which holds twice as many chunks;
+/// splits into halves recursively (Karatsuba) while the operands are longer than `threshold`
+fn mul_chunks(one: &[u64], other: &[u64], prod: &mut [u64], threshold: usize) {
+    let n = one.len();
+    if n <= threshold.max(1) {
+        schoolbook_mul_chunks(one, other, prod);
+        return;
+    }
+    let half = n / 2;
+    let (one_low, one_high) = one.split_at(half);
+    let (other_low, other_high) = other.split_at(half);
+    // low and high products go straight into their places in prod
+    let (prod_low, prod_high) = prod.split_at_mut(2 * half);
+    mul_chunks(one_low, other_low, prod_low, threshold);
+    mul_chunks(one_high, other_high, prod_high, threshold);
+    // sums of halves, each of length n - half plus a carry bit
+    let high_len = n - half;
+    let mut one_sum = one_high.to_vec();
+    let one_carry = add_chunks(&mut one_sum, one_low);
+    let mut other_sum = other_high.to_vec();
+    let other_carry = add_chunks(&mut other_sum, other_low);
+    // mid = (one_low + one_high) * (other_low + other_high) - low - high
+    let mut mid = vec![0u64; 2 * high_len + 1];
+    mul_chunks(&one_sum, &other_sum, &mut mid[..2 * high_len], threshold);
+    if one_carry {
+        add_chunks(&mut mid[high_len..], &other_sum);
     }
+    if other_carry {
+        add_chunks(&mut mid[high_len..], &one_sum);
+    }
+    if one_carry && other_carry {
+        add_chunks(&mut mid[2 * high_len..], &[1]);
+    }
+    sub_chunks(&mut mid, &prod[..2 * half]);
+    sub_chunks(&mut mid, &prod[2 * half..]);
+    add_chunks(&mut prod[half..], &mid);
+}
+
+fn widening_mul_chunk(one: u64, other: u64) -> (u64, u64) {
+    let prod = (one as u128) * (other as u128);
+    (prod as u64, (prod >> 64) as u64)
+}
+
+fn significant_chunks(chunks: &[u64]) -> usize {
+    chunks.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1)
 }
 
-impl BigUInt4096 {
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// wraps raw chunks, counting how many of them are significant
+    const fn from_chunks(chunks: [u64; LIMBS]) -> Self {
+        let mut len = LIMBS;
+        while len > 0 && chunks[len - 1] == 0 {
+            len -= 1;
+        }
+        Self { chunks, len }
+    }
+
+    /// wraps raw chunks without looking at them, for values that must not be inspected
+    fn from_chunks_full(chunks: [u64; LIMBS]) -> Self {
+        Self { chunks, len: LIMBS }
+    }
+
+    /// tightens `len` after a write, given that every chunk from index `bound` up is zero
+    fn trim_len(&mut self, bound: usize) {
+        self.len = significant_chunks(&self.chunks[..bound.min(LIMBS)]);
+    }
+
+    /// the significant chunks, least significant first
+    fn digits(&self) -> &[u64] {
+        &self.chunks[..self.len]
+    }
+}
+
+impl<const WIDE: usize> BigUInt<WIDE> {
+    /// joins a low and a high half into a double-width integer
+    pub fn from_halves<const LIMBS: usize>(low: &BigUInt<LIMBS>, high: &BigUInt<LIMBS>) -> Self {
+        const { assert!(WIDE == 2 * LIMBS, "Halves must be half the width") };
+        let mut newint = Self::default();
+        newint.chunks[..LIMBS].clone_from_slice(&low.chunks);
+        newint.chunks[LIMBS..].clone_from_slice(&high.chunks);
+        newint.len = if high.len > 0 {
+            LIMBS + high.len
+        } else {
+            low.len
+        };
+        newint
+    }
+
+    /// splits a double-width integer into its low and high halves
+    pub fn split<const LIMBS: usize>(&self) -> (BigUInt<LIMBS>, BigUInt<LIMBS>) {
+        const { assert!(WIDE == 2 * LIMBS, "Halves must be half the width") };
+        let mut left = [0u64; LIMBS];
+        let mut right = [0u64; LIMBS];
+        left.clone_from_slice(&self.chunks[..LIMBS]);
+        right.clone_from_slice(&self.chunks[LIMBS..]);
+        (
+            BigUInt {
+                chunks: left,
+                len: self.len.min(LIMBS),
+            },
+            BigUInt {
+                chunks: right,
+                len: self.len.saturating_sub(LIMBS),
+            },
+        )
+    }
+}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    pub const ZERO: Self = Self {
+        chunks: [0u64; LIMBS],
+        len: 0,
+    };
+
+    pub const ONE: Self = {
+        let mut chunks = [0u64; LIMBS];
+        chunks[0] = 1;
+        Self { chunks, len: 1 }
+    };
+
+    pub const MAX: Self = Self {
+        chunks: [u64::MAX; LIMBS],
+        len: LIMBS,
+    };
+
     pub fn new(init: &[u64]) -> Self {
-        if init.len() > 64 {
+        if init.len() > LIMBS {
             panic!("Invalid initialisation attempt");
         }
         let mut newint = Self::default();
         newint.chunks[..init.len()].clone_from_slice(init);
+        newint.trim_len(init.len());
         newint
     }
 
     pub fn new_from_right(init: &[u64]) -> Self {
-        if init.len() > 64 {
+        if init.len() > LIMBS {
             panic!("Invalid initialisation attempt");
         }
         let mut newint = Self::default();
-        newint.chunks[64 - init.len()..].clone_from_slice(init);
+        newint.chunks[LIMBS - init.len()..].clone_from_slice(init);
+        newint.trim_len(LIMBS);
         newint
     }
 
     pub fn new_from_middle(init: &[u64], start: usize) -> Self {
-        if start + init.len() > 64 {
+        if start + init.len() > LIMBS {
             panic!("Invalid initialisation attempt");
         }
         let mut newint = Self::default();
         newint.chunks[start..start + init.len()].clone_from_slice(init);
+        newint.trim_len(start + init.len());
         newint
     }
 
-    fn overflowing_add(&self, other: &Self) -> (Self, bool) {
-        let mut sum = Self::default();
-        let mut carry: bool = false;
-        let mut carry2: bool;
-        // convention: least significant u64 is at index 0
-        for i in 0..64 {
-            (sum.chunks[i], carry2) = self.chunks[i].overflowing_add(u64::from(carry));
-            (sum.chunks[i], carry) = sum.chunks[i].overflowing_add(other.chunks[i]);
-            carry = carry || carry2;
-        }
+    pub fn is_zero(&self) -> bool {
+        self.digits().iter().all(|&chunk| chunk == 0)
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.len > 0 && self.chunks[0] == 1 && self.digits()[1..].iter().all(|&chunk| chunk == 0)
+    }
+
+    /// adds `other` in place, only touching the chunks either operand uses;
+    /// returns the carry out of the top chunk
+    fn add_assign_chunks(&mut self, other: &Self) -> bool {
+        let bound = self.len.max(other.len) + 1;
+        let carry = add_chunks(&mut self.chunks, other.digits());
+        self.trim_len(bound);
+        carry
+    }
+
+    /// subtracts `other` in place, only touching the chunks either operand uses unless it
+    /// borrows past zero; returns the borrow out of the top chunk
+    fn sub_assign_chunks(&mut self, other: &Self) -> bool {
+        let borrow = sub_chunks(&mut self.chunks, other.digits());
+        let bound = if borrow {
+            LIMBS
+        } else {
+            self.len.max(other.len)
+        };
+        self.trim_len(bound);
+        borrow
+    }
+
+    /// returns the wrapped sum and whether the addition overflowed
+    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut sum = self.clone();
+        let carry = sum.add_assign_chunks(other);
         (sum, carry)
     }
 
-    fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
-        let mut diff = Self::default();
-        let mut borrow: bool = false;
-        let mut borrow2: bool;
-        // convention: least significant u64 is at index 0
-        for i in 0..64 {
-            (diff.chunks[i], borrow2) = self.chunks[i].overflowing_sub(u64::from(borrow));
-            (diff.chunks[i], borrow) = diff.chunks[i].overflowing_sub(other.chunks[i]);
-            borrow = borrow || borrow2;
-        }
+    /// returns the wrapped difference and whether the subtraction borrowed past zero
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        let mut diff = self.clone();
+        let borrow = diff.sub_assign_chunks(other);
         (diff, borrow)
     }
 
-    fn widening_mul(one: u64, other: u64) -> (u64, u64) {
-        let prod = (one as u128) * (other as u128);
-        (prod as u64, (prod >> 64) as u64)
-    }
-
-    fn overflowing_mul(&self, other: &Self) -> (Self, Self) {
-        let mut prod = BigUInt8192::default();
-        let mut rowprod = BigUInt8192::default();
-        let mut chunkprod: u64;
-        let mut carry: u64;
-        for i in 0..64 {
-            rowprod.reset();
-            for j in 0..64 {
-                (chunkprod, carry) = Self::widening_mul(self.chunks[i], other.chunks[j]);
-                (rowprod.chunks[i + j], rowprod.chunks[i + j + 1]) =
-                    match rowprod.chunks[i + j].overflowing_add(chunkprod) {
-                        (sum, true) => (sum, carry + 1),
-                        (sum, _) => (sum, carry),
-                    };
+    /// full product as low and high halves, laid out contiguously
+    fn full_mul(&self, other: &Self) -> [[u64; LIMBS]; 2] {
+        self.full_mul_with_threshold(other, KARATSUBA_THRESHOLD)
+    }
+
+    /// only the significant chunks are multiplied: operands of unequal length go straight to
+    /// schoolbook when the shorter one is within the threshold, and otherwise both are taken at
+    /// the longer length for Karatsuba
+    fn full_mul_with_threshold(&self, other: &Self, threshold: usize) -> [[u64; LIMBS]; 2] {
+        let mut prod = [[0u64; LIMBS]; 2];
+        let (short, long) = if self.len <= other.len {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if short.len == 0 {
+            return prod;
+        }
+        let flat = prod.as_flattened_mut();
+        if short.len <= threshold.max(1) {
+            schoolbook_mul_chunks(
+                short.digits(),
+                long.digits(),
+                &mut flat[..short.len + long.len],
+            );
+        } else {
+            let n = long.len;
+            mul_chunks(
+                &short.chunks[..n],
+                &long.chunks[..n],
+                &mut flat[..2 * n],
+                threshold,
+            );
+        }
+        prod
+    }
+
+    /// significant length bound for the product of `self` and `other`
+    fn mul_len(&self, other: &Self) -> usize {
+        if self.len == 0 || other.len == 0 {
+            0
+        } else {
+            self.len + other.len
+        }
+    }
+
+    /// returns the low half of the product and whether the high half was non-zero
+    pub fn overflowing_mul(&self, other: &Self) -> (Self, bool) {
+        let bound = self.mul_len(other);
+        let [low, high] = self.full_mul(other);
+        let mut prod = Self {
+            chunks: low,
+            len: 0,
+        };
+        prod.trim_len(bound);
+        (prod, bound > LIMBS && high.iter().any(|&chunk| chunk != 0))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        match self.overflowing_add(other) {
+            (sum, false) => Some(sum),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        match self.overflowing_sub(other) {
+            (diff, false) => Some(diff),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        match self.overflowing_mul(other) {
+            (prod, false) => Some(prod),
+            _ => None,
+        }
+    }
+
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        self.overflowing_add(other).0
+    }
+
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        self.overflowing_sub(other).0
+    }
+
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        self.overflowing_mul(other).0
+    }
+
+    /// returns the sum, or the largest value if the addition overflowed
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        self.checked_add(other).unwrap_or(Self::MAX)
+    }
+
+    /// returns the difference, or zero if the subtraction borrowed past zero
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        self.checked_sub(other).unwrap_or_default()
+    }
+
+    /// returns the product, or the largest value if the multiplication overflowed
+    pub fn saturating_mul(&self, other: &Self) -> Self {
+        self.checked_mul(other).unwrap_or(Self::MAX)
+    }
+
+    /// returns the full product in an integer of twice the width
+    pub fn widening_mul<const WIDE: usize>(&self, other: &Self) -> BigUInt<WIDE> {
+        const { assert!(WIDE == 2 * LIMBS, "Product must be twice the width") };
+        let mut prod = BigUInt::<WIDE>::default();
+        prod.chunks
+            .clone_from_slice(self.full_mul(other).as_flattened());
+        prod.trim_len(self.mul_len(other));
+        prod
+    }
+
+    /// as `widening_mul`, but with a custom Karatsuba threshold in chunks;
+    /// a threshold of at least `LIMBS` gives a plain schoolbook multiplication
+    pub fn widening_mul_with_threshold<const WIDE: usize>(
+        &self,
+        other: &Self,
+        threshold: usize,
+    ) -> BigUInt<WIDE> {
+        const { assert!(WIDE == 2 * LIMBS, "Product must be twice the width") };
+        let mut prod = BigUInt::<WIDE>::default();
+        prod.chunks.clone_from_slice(
+            self.full_mul_with_threshold(other, threshold)
+                .as_flattened(),
+        );
+        prod.trim_len(self.mul_len(other));
+        prod
+    }
+
+    fn div_rem_chunk(&self, divisor: u64) -> (Self, u64) {
+        let mut quot = Self::default();
+        let mut rem: u64 = 0;
+        for i in (0..self.len).rev() {
+            let num = ((rem as u128) << 64) | (self.chunks[i] as u128);
+            quot.chunks[i] = (num / divisor as u128) as u64;
+            rem = (num % divisor as u128) as u64;
+        }
+        quot.trim_len(self.len);
+        (quot, rem)
+    }
+
+    /// long division of `num` by `den` (Knuth, TAOCP vol. 2, algorithm D)
+    /// `num` holds the normalised dividend plus one extra chunk and is left holding the remainder;
+    /// `den` is the normalised divisor, with at least two chunks and its top bit set
+    fn div_rem_knuth(num: &mut [u64], den: &[u64], quot: &mut [u64]) {
+        let n = den.len();
+        let top = den[n - 1] as u128;
+        let next = den[n - 2] as u128;
+        for j in (0..num.len() - n).rev() {
+            // estimate the quotient chunk from the top two chunks of the running remainder
+            let num2 = ((num[j + n] as u128) << 64) | (num[j + n - 1] as u128);
+            let mut qhat = num2 / top;
+            let mut rhat = num2 % top;
+            while qhat > u64::MAX as u128 || qhat * next > ((rhat << 64) | num[j + n - 2] as u128) {
+                qhat -= 1;
+                rhat += top;
+                if rhat > u64::MAX as u128 {
+                    break;
+                }
+            }
+            // subtract qhat * den from the running remainder
+            let mut carry: u64 = 0;
+            let mut borrow: bool = false;
+            let mut borrow2: bool;
+            let mut chunkprod: u64;
+            let mut high: u64;
+            for i in 0..n {
+                (chunkprod, high) = widening_mul_chunk(qhat as u64, den[i]);
+                (chunkprod, carry) = match chunkprod.overflowing_add(carry) {
+                    (sum, true) => (sum, high + 1),
+                    (sum, _) => (sum, high),
+                };
+                (num[j + i], borrow2) = num[j + i].overflowing_sub(u64::from(borrow));
+                (num[j + i], borrow) = num[j + i].overflowing_sub(chunkprod);
+                borrow = borrow || borrow2;
             }
-            (prod, _) = prod.overflowing_add(&rowprod);
+            (num[j + n], borrow2) = num[j + n].overflowing_sub(u64::from(borrow));
+            (num[j + n], borrow) = num[j + n].overflowing_sub(carry);
+            borrow = borrow || borrow2;
+            // qhat was still one too large: add den back
+            if borrow {
+                qhat -= 1;
+                let carry = add_chunks(&mut num[j..j + n], den);
+                num[j + n] = num[j + n].wrapping_add(u64::from(carry));
+            }
+            quot[j] = qhat as u64;
+        }
+    }
+
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.checked_div_rem(other).map(|(quot, _)| quot)
+    }
+
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        self.checked_div_rem(other).map(|(_, rem)| rem)
+    }
+
+    /// returns `None` if `other` is zero, otherwise the quotient and remainder of `self / other`
+    pub fn checked_div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        let den_len = significant_chunks(other.digits());
+        let num_len = significant_chunks(self.digits());
+        if den_len == 0 {
+            return None;
+        }
+        if den_len > num_len {
+            return Some((Self::default(), self.clone()));
         }
-        prod.split()
+        if den_len == 1 {
+            let (quot, rem) = self.div_rem_chunk(other.chunks[0]);
+            return Some((quot, Self::new(&[rem])));
+        }
+        // normalise so that the top chunk of the divisor has its top bit set
+        let shift = other.chunks[den_len - 1].leading_zeros();
+        let mut num_buf = [[0u64; LIMBS]; 2];
+        let num = num_buf.as_flattened_mut();
+        let mut den = [0u64; LIMBS];
+        for i in 0..LIMBS {
+            let low = if shift > 0 && i > 0 {
+                self.chunks[i - 1] >> (64 - shift)
+            } else {
+                0
+            };
+            num[i] = (self.chunks[i] << shift) | low;
+            let low = if shift > 0 && i > 0 {
+                other.chunks[i - 1] >> (64 - shift)
+            } else {
+                0
+            };
+            den[i] = (other.chunks[i] << shift) | low;
+        }
+        if shift > 0 {
+            num[LIMBS] = self.chunks[LIMBS - 1] >> (64 - shift);
+        }
+        let mut quot = Self::default();
+        Self::div_rem_knuth(
+            &mut num[..num_len + 1],
+            &den[..den_len],
+            &mut quot.chunks[..],
+        );
+        quot.trim_len(num_len + 1 - den_len);
+        // undo the normalisation on the remainder
+        let mut rem = Self::default();
+        for i in 0..den_len {
+            let high = if shift > 0 {
+                num[i + 1] << (64 - shift)
+            } else {
+                0
+            };
+            rem.chunks[i] = (num[i] >> shift) | high;
+        }
+        rem.trim_len(den_len);
+        Some((quot, rem))
+    }
+
+    /// returns the quotient and remainder of `self / other`, panicking if `other` is zero
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        self.checked_div_rem(other).expect("Division by zero")
     }
 }
 
-impl std::ops::Add<BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Add<BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn add(self, other: BigUInt4096) -> Self::Output {
+    fn add(self, other: BigUInt<LIMBS>) -> Self::Output {
         let (sum, carry) = self.overflowing_add(&other);
         debug_assert!(!carry, "Overflow while adding");
         sum
     }
 }
 
-impl std::ops::Add<&BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Add<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn add(self, other: &BigUInt4096) -> Self::Output {
+    fn add(self, other: &BigUInt<LIMBS>) -> Self::Output {
         let (sum, carry) = self.overflowing_add(other);
         debug_assert!(!carry, "Overflow while adding");
         sum
     }
 }
 
-impl std::ops::Add<BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Add<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
 
-    fn add(self, other: BigUInt4096) -> Self::Output {
+    fn add(self, other: BigUInt<LIMBS>) -> Self::Output {
         let (sum, carry) = self.overflowing_add(&other);
         debug_assert!(!carry, "Overflow while adding");
         sum
     }
 }
 
-impl std::ops::Add<&BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Add<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
 
-    fn add(self, other: &BigUInt4096) -> Self::Output {
+    fn add(self, other: &BigUInt<LIMBS>) -> Self::Output {
         let (sum, carry) = self.overflowing_add(other);
         debug_assert!(!carry, "Overflow while adding");
         sum
     }
 }
 
-impl std::ops::Sub<BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Sub<BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn sub(self, other: BigUInt4096) -> Self::Output {
+    fn sub(self, other: BigUInt<LIMBS>) -> Self::Output {
         let (diff, borrow) = self.overflowing_sub(&other);
         debug_assert!(!borrow, "Overflow while subtracting");
         diff
     }
 }
 
-impl std::ops::Sub<&BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Sub<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn sub(self, other: &BigUInt4096) -> Self::Output {
+    fn sub(self, other: &BigUInt<LIMBS>) -> Self::Output {
         let (diff, borrow) = self.overflowing_sub(other);
         debug_assert!(!borrow, "Overflow while subtracting");
         diff
     }
 }
 
-impl std::ops::Sub<BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Sub<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
 
-    fn sub(self, other: BigUInt4096) -> Self::Output {
+    fn sub(self, other: BigUInt<LIMBS>) -> Self::Output {
         let (diff, borrow) = self.overflowing_sub(&other);
         debug_assert!(!borrow, "Overflow while subtracting");
         diff
     }
 }
 
-impl std::ops::Sub<&BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Sub<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
 
-    fn sub(self, other: &BigUInt4096) -> Self::Output {
+    fn sub(self, other: &BigUInt<LIMBS>) -> Self::Output {
         let (diff, borrow) = self.overflowing_sub(other);
         debug_assert!(!borrow, "Overflow while subtracting");
         diff
     }
 }
 
-impl std::ops::Mul<BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Mul<BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn mul(self, other: BigUInt4096) -> Self::Output {
-        let (prod, carry) = self.overflowing_mul(&other);
-        debug_assert_eq!(carry, BigUInt4096::default(), "Overflow while multiplying");
+    fn mul(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (prod, overflow) = self.overflowing_mul(&other);
+        debug_assert!(!overflow, "Overflow while multiplying");
         prod
     }
 }
 
-impl std::ops::Mul<&BigUInt4096> for BigUInt4096 {
+impl<const LIMBS: usize> std::ops::Mul<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
     type Output = Self;
 
-    fn mul(self, other: &BigUInt4096) -> Self::Output {
-        let (prod, carry) = self.overflowing_mul(other);
-        debug_assert_eq!(carry, BigUInt4096::default(), "Overflow while multiplying");
+    fn mul(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (prod, overflow) = self.overflowing_mul(other);
+        debug_assert!(!overflow, "Overflow while multiplying");
+        prod
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Mul<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn mul(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (prod, overflow) = self.overflowing_mul(&other);
+        debug_assert!(!overflow, "Overflow while multiplying");
         prod
     }
 }
 
-impl std::ops::Mul<BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Mul<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
 
-    fn mul(self, other: BigUInt4096) -> Self::Output {
-        let (prod, carry) = self.overflowing_mul(&other);
-        debug_assert_eq!(carry, BigUInt4096::default(), "Overflow while multiplying");
+    fn mul(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (prod, overflow) = self.overflowing_mul(other);
+        debug_assert!(!overflow, "Overflow while multiplying");
         prod
     }
 }
 
-impl std::ops::Mul<&BigUInt4096> for &BigUInt4096 {
-    type Output = BigUInt4096;
+impl<const LIMBS: usize> std::ops::Div<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn div(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (quot, _) = self.div_rem(&other);
+        quot
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn div(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (quot, _) = self.div_rem(other);
+        quot
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn div(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (quot, _) = self.div_rem(&other);
+        quot
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn div(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (quot, _) = self.div_rem(other);
+        quot
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn rem(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (_, rem) = self.div_rem(&other);
+        rem
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn rem(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (_, rem) = self.div_rem(other);
+        rem
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn rem(self, other: BigUInt<LIMBS>) -> Self::Output {
+        let (_, rem) = self.div_rem(&other);
+        rem
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<&BigUInt<LIMBS>> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn rem(self, other: &BigUInt<LIMBS>) -> Self::Output {
+        let (_, rem) = self.div_rem(other);
+        rem
+    }
+}
+
+impl<const LIMBS: usize> std::ops::AddAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn add_assign(&mut self, other: BigUInt<LIMBS>) {
+        let carry = self.add_assign_chunks(&other);
+        debug_assert!(!carry, "Overflow while adding");
+    }
+}
+
+impl<const LIMBS: usize> std::ops::AddAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn add_assign(&mut self, other: &BigUInt<LIMBS>) {
+        let carry = self.add_assign_chunks(other);
+        debug_assert!(!carry, "Overflow while adding");
+    }
+}
+
+impl<const LIMBS: usize> std::ops::SubAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn sub_assign(&mut self, other: BigUInt<LIMBS>) {
+        let borrow = self.sub_assign_chunks(&other);
+        debug_assert!(!borrow, "Overflow while subtracting");
+    }
+}
+
+impl<const LIMBS: usize> std::ops::SubAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn sub_assign(&mut self, other: &BigUInt<LIMBS>) {
+        let borrow = self.sub_assign_chunks(other);
+        debug_assert!(!borrow, "Overflow while subtracting");
+    }
+}
+
+impl<const LIMBS: usize> std::ops::MulAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn mul_assign(&mut self, other: BigUInt<LIMBS>) {
+        let bound = self.mul_len(&other);
+        let [low, high] = self.full_mul(&other);
+        debug_assert!(
+            high.iter().all(|&chunk| chunk == 0),
+            "Overflow while multiplying"
+        );
+        self.chunks = low;
+        self.trim_len(bound);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::MulAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn mul_assign(&mut self, other: &BigUInt<LIMBS>) {
+        let bound = self.mul_len(other);
+        let [low, high] = self.full_mul(other);
+        debug_assert!(
+            high.iter().all(|&chunk| chunk == 0),
+            "Overflow while multiplying"
+        );
+        self.chunks = low;
+        self.trim_len(bound);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::DivAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn div_assign(&mut self, other: BigUInt<LIMBS>) {
+        (*self, _) = self.div_rem(&other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::DivAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn div_assign(&mut self, other: &BigUInt<LIMBS>) {
+        (*self, _) = self.div_rem(other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::RemAssign<BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn rem_assign(&mut self, other: BigUInt<LIMBS>) {
+        (_, *self) = self.div_rem(&other);
+    }
+}
+
+impl<const LIMBS: usize> std::ops::RemAssign<&BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn rem_assign(&mut self, other: &BigUInt<LIMBS>) {
+        (_, *self) = self.div_rem(other);
+    }
+}
+
+impl<const LIMBS: usize> std::iter::Sum for BigUInt<LIMBS> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut sum = Self::default();
+        for item in iter {
+            sum += item;
+        }
+        sum
+    }
+}
 
-    fn mul(self, other: &BigUInt4096) -> Self::Output {
-        let (prod, carry) = self.overflowing_mul(other);
-        debug_assert_eq!(carry, BigUInt4096::default(), "Overflow while multiplying");
+impl<'a, const LIMBS: usize> std::iter::Sum<&'a BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn sum<I: Iterator<Item = &'a BigUInt<LIMBS>>>(iter: I) -> Self {
+        let mut sum = Self::default();
+        for item in iter {
+            sum += item;
+        }
+        sum
+    }
+}
+
+impl<const LIMBS: usize> std::iter::Product for BigUInt<LIMBS> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut prod = Self::new(&[1u64]);
+        for item in iter {
+            prod *= item;
+        }
+        prod
+    }
+}
+
+impl<'a, const LIMBS: usize> std::iter::Product<&'a BigUInt<LIMBS>> for BigUInt<LIMBS> {
+    fn product<I: Iterator<Item = &'a BigUInt<LIMBS>>>(iter: I) -> Self {
+        let mut prod = Self::new(&[1u64]);
+        for item in iter {
+            prod *= item;
+        }
         prod
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BigUInt4096;
+    use crate::random::TestRng;
+    use crate::{
+        significant_chunks, widening_mul_chunk, BigUInt, BigUInt256, BigUInt4096, BigUInt512,
+        BigUInt8192,
+    };
 
     // test from-left constructor
     #[test]
@@ -279,12 +939,12 @@ mod tests {
         assert_eq!(num1, num2);
     }
 
-    // test widening-mul implementation
+    // test widening-mul implementation for single chunks
     #[test]
-    fn widening_mul_test() {
+    fn widening_mul_chunk_test() {
         let one = 0xab00000000u64;
         let other = 0xbc00000000u64;
-        assert_eq!(BigUInt4096::widening_mul(one, other), (0u64, 0x7d94u64));
+        assert_eq!(widening_mul_chunk(one, other), (0u64, 0x7d94u64));
     }
 
     // test overflowing-add for BigUInt4096 with no carry
@@ -346,7 +1006,9 @@ mod tests {
         let num1 = BigUInt4096::new_from_middle(&[0xd9u64], 26);
         let num2 = BigUInt4096::new_from_middle(&[0x45u64], 14);
         let num3 = BigUInt4096::new_from_middle(&[0x3a7du64], 40);
-        assert_eq!(num1.overflowing_mul(&num2), (num3, BigUInt4096::default()));
+        assert_eq!(num1.overflowing_mul(&num2), (num3.clone(), false));
+        let prod: BigUInt8192 = num1.widening_mul(&num2);
+        assert_eq!(prod.split(), (num3, BigUInt4096::default()));
     }
 
     // test overflowing-mul for BigUInt4096 with multiple non-zero chunks
@@ -355,7 +1017,9 @@ mod tests {
         let num1 = BigUInt4096::new_from_middle(&[0x1u64, 0x1u64], 20);
         let num2 = BigUInt4096::new_from_middle(&[0x1u64, 0x1u64], 32);
         let num3 = BigUInt4096::new_from_middle(&[0x1u64, 0x2u64, 0x1u64], 52);
-        assert_eq!(num1.overflowing_mul(&num2), (num3, BigUInt4096::default()));
+        assert_eq!(num1.overflowing_mul(&num2), (num3.clone(), false));
+        let prod: BigUInt8192 = num1.widening_mul(&num2);
+        assert_eq!(prod.split(), (num3, BigUInt4096::default()));
     }
 
     // test overflowing-mul for BigUInt4096 with single non-zero chunks and overflow
@@ -364,7 +1028,9 @@ mod tests {
         let num1 = BigUInt4096::new_from_middle(&[0x7fu64], 40);
         let num2 = BigUInt4096::new_from_middle(&[0xeeu64], 34);
         let num3 = BigUInt4096::new_from_middle(&[0x7612u64], 10);
-        assert_eq!(num1.overflowing_mul(&num2), (BigUInt4096::default(), num3));
+        assert_eq!(num1.overflowing_mul(&num2), (BigUInt4096::default(), true));
+        let prod: BigUInt8192 = num1.widening_mul(&num2);
+        assert_eq!(prod.split(), (BigUInt4096::default(), num3));
     }
 
     // the corresponding tests for the operator versions are below
@@ -458,4 +1124,424 @@ mod tests {
         let num2 = BigUInt4096::new_from_middle(&[0xeeu64], 34);
         println!("{}", (num1 * num2).chunks[0]);
     }
+
+    // test division by a single-chunk divisor
+    #[test]
+    fn div_rem_4096_test1() {
+        let num1 = BigUInt4096::new(&[0x1u64, 0xfedcba9876543210u64, 0x1u64]);
+        let num2 = BigUInt4096::new(&[0x1000000000000003u64]);
+        let quot = BigUInt4096::new(&[0xedcba98765431b03u64, 0x1fu64]);
+        let rem = BigUInt4096::new(&[0x69d0369d036aef8u64]);
+        assert_eq!(num1.div_rem(&num2), (quot, rem));
+    }
+
+    // test division by a full 64-chunk divisor
+    #[test]
+    fn div_rem_4096_test2() {
+        let num1 = BigUInt4096::new(&[u64::MAX; 64]);
+        let num2 = BigUInt4096::new(&[0x0123456789abcdefu64; 64]);
+        let quot = BigUInt4096::new(&[0xe1u64]);
+        let rem = BigUInt4096::new(&[0xf0u64; 64]);
+        assert_eq!(num1.div_rem(&num2), (quot, rem));
+    }
+
+    // test division where the first quotient estimate is one too large
+    #[test]
+    fn div_rem_4096_test3() {
+        let num1 = BigUInt4096::new(&[
+            0x1u64,
+            0xfffffffffffffffeu64,
+            0x8000000000000000u64,
+            0xfffffffffffffffeu64,
+            0x1u64,
+            0xfffffffffffffffeu64,
+        ]);
+        let num2 = BigUInt4096::new(&[
+            u64::MAX,
+            u64::MAX,
+            0x8000000000000000u64,
+            0x7fffffffffffffffu64,
+        ]);
+        let quot = BigUInt4096::new(&[0xfffffffffffffffdu64, 0xfffffffffffffffdu64, 0x1u64]);
+        let rem = BigUInt4096::new(&[
+            0xfffffffffffffffeu64,
+            0xfffffffffffffffbu64,
+            0x5u64,
+            0x7fffffffffffffffu64,
+        ]);
+        assert_eq!(num1.div_rem(&num2), (quot, rem));
+    }
+
+    // test division with a divisor larger than the dividend
+    #[test]
+    fn div_rem_4096_test4() {
+        let num1 = BigUInt4096::new_from_middle(&[0x5u64], 20);
+        let num2 = BigUInt4096::new_from_middle(&[0x1u64], 21);
+        assert_eq!(num1.div_rem(&num2), (BigUInt4096::default(), num1.clone()));
+    }
+
+    // test that quotient and remainder recombine into the dividend for divisors of every length
+    #[test]
+    fn div_rem_4096_test5() {
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for len in 1..=64 {
+            let num1 = BigUInt4096::new(&(0..64).map(|_| next()).collect::<Vec<u64>>());
+            let num2 = BigUInt4096::new(&(0..len).map(|_| next()).collect::<Vec<u64>>());
+            let (quot, rem) = num1.div_rem(&num2);
+            let (prod, overflow) = quot.overflowing_mul(&num2);
+            assert!(!overflow);
+            assert_eq!(prod + &rem, num1);
+            assert!(rem < num2);
+        }
+    }
+
+    // test checked division by zero
+    #[test]
+    fn checked_div_rem_4096_test() {
+        let num1 = BigUInt4096::new(&[0x5u64, 0x7u64]);
+        assert_eq!(num1.checked_div_rem(&BigUInt4096::default()), None);
+    }
+
+    #[test]
+    fn div_test1() {
+        let num1 = BigUInt4096::new(&[0x1u64, 0xfedcba9876543210u64, 0x1u64]);
+        let num2 = BigUInt4096::new(&[0x1000000000000003u64]);
+        let num3 = BigUInt4096::new(&[0xedcba98765431b03u64, 0x1fu64]);
+        assert_eq!(num1.clone() / num2.clone(), num3);
+        assert_eq!(num1.clone() / &num2, num3);
+        assert_eq!(&num1 / num2.clone(), num3);
+        assert_eq!(&num1 / &num2, num3);
+    }
+
+    #[test]
+    fn div_test2() {
+        let num1 = BigUInt4096::new(&[u64::MAX; 64]);
+        let num2 = BigUInt4096::new(&[0x0123456789abcdefu64; 64]);
+        let num3 = BigUInt4096::new(&[0xe1u64]);
+        assert_eq!(num1.clone() / num2.clone(), num3);
+        assert_eq!(num1.clone() / &num2, num3);
+        assert_eq!(&num1 / num2.clone(), num3);
+        assert_eq!(&num1 / &num2, num3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_test3() {
+        let num1 = BigUInt4096::new(&[1u64]);
+        println!("{}", (num1 / BigUInt4096::default()).chunks[0]);
+    }
+
+    #[test]
+    fn rem_test1() {
+        let num1 = BigUInt4096::new(&[0x1u64, 0xfedcba9876543210u64, 0x1u64]);
+        let num2 = BigUInt4096::new(&[0x1000000000000003u64]);
+        let num3 = BigUInt4096::new(&[0x69d0369d036aef8u64]);
+        assert_eq!(num1.clone() % num2.clone(), num3);
+        assert_eq!(num1.clone() % &num2, num3);
+        assert_eq!(&num1 % num2.clone(), num3);
+        assert_eq!(&num1 % &num2, num3);
+    }
+
+    #[test]
+    fn rem_test2() {
+        let num1 = BigUInt4096::new(&[u64::MAX; 64]);
+        let num2 = BigUInt4096::new(&[0x0123456789abcdefu64; 64]);
+        let num3 = BigUInt4096::new(&[0xf0u64; 64]);
+        assert_eq!(num1.clone() % num2.clone(), num3);
+        assert_eq!(num1.clone() % &num2, num3);
+        assert_eq!(&num1 % num2.clone(), num3);
+        assert_eq!(&num1 % &num2, num3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rem_test3() {
+        let num1 = BigUInt4096::new(&[1u64]);
+        println!("{}", (num1 % BigUInt4096::default()).chunks[0]);
+    }
+
+    // test operators on a narrower width
+    #[test]
+    fn ops_256_test() {
+        let num1 = BigUInt256::new(&[u64::MAX, 0x1u64]);
+        let num2 = BigUInt256::new(&[0x2u64]);
+        assert_eq!(&num1 + &num2, BigUInt256::new(&[0x1u64, 0x2u64]));
+        assert_eq!(&num1 - &num2, BigUInt256::new(&[u64::MAX - 2, 0x1u64]));
+        assert_eq!(&num1 * &num2, BigUInt256::new(&[u64::MAX - 1, 0x3u64]));
+        assert_eq!(&num1 / &num2, BigUInt256::new(&[u64::MAX, 0x0u64]));
+        assert_eq!(&num1 % &num2, BigUInt256::new(&[0x1u64]));
+    }
+
+    // test overflow detection at a narrower width
+    #[test]
+    #[should_panic]
+    fn ops_256_overflow_test() {
+        let num1 = BigUInt256::new_from_right(&[0x1u64, 0x0u64]);
+        println!("{:?}", &num1 * &num1);
+    }
+
+    // test widening multiplication into the double-width type
+    #[test]
+    fn widening_mul_test() {
+        let num1 = BigUInt256::new_from_right(&[u64::MAX]);
+        let num2 = BigUInt256::new_from_right(&[0x2u64]);
+        let prod: BigUInt512 = num1.widening_mul(&num2);
+        assert_eq!(
+            prod,
+            BigUInt512::new_from_middle(&[u64::MAX - 1, 0x1u64], 6)
+        );
+        let num3 = BigUInt4096::new_from_middle(&[0x7fu64], 40);
+        let num4 = BigUInt4096::new_from_middle(&[0xeeu64], 34);
+        let prod: BigUInt8192 = num3.widening_mul(&num4);
+        assert_eq!(prod, BigUInt8192::new_from_middle(&[0x7612u64], 74));
+    }
+
+    // test splitting a double-width integer into halves and joining them again
+    #[test]
+    fn split_test() {
+        let wide = BigUInt512::new(&[0x1u64, 0x2u64, 0x3u64, 0x4u64, 0x5u64, 0x6u64]);
+        let (low, high): (BigUInt256, BigUInt256) = wide.split();
+        assert_eq!(low, BigUInt256::new(&[0x1u64, 0x2u64, 0x3u64, 0x4u64]));
+        assert_eq!(high, BigUInt256::new(&[0x5u64, 0x6u64]));
+        assert_eq!(BigUInt512::from_halves(&low, &high), wide);
+    }
+
+    // test that Karatsuba agrees with schoolbook multiplication, including odd splits and carries
+    #[test]
+    fn karatsuba_mul_test() {
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let num1 = BigUInt4096::new(&(0..64).map(|_| next()).collect::<Vec<u64>>());
+        let num2 = BigUInt4096::new(&(0..64).map(|_| next()).collect::<Vec<u64>>());
+        let num3 = BigUInt4096::new(&[u64::MAX; 64]);
+        for (one, other) in [(&num1, &num2), (&num3, &num3), (&num1, &num3)] {
+            let expected: BigUInt8192 = one.widening_mul_with_threshold(other, usize::MAX);
+            for threshold in [1, 3, 7, 32] {
+                assert_eq!(one.widening_mul_with_threshold(other, threshold), expected);
+            }
+            assert_eq!(one.widening_mul(other), expected);
+        }
+    }
+
+    // test that ordering is numeric, with the most significant chunk deciding first
+    #[test]
+    fn cmp_test() {
+        let num1 = BigUInt4096::new(&[u64::MAX, 0x0u64]);
+        let num2 = BigUInt4096::new(&[0x0u64, 0x1u64]);
+        assert!(num1 < num2);
+        assert!(num2 > num1);
+        assert!(num1 <= num1.clone());
+        let num3 = BigUInt4096::new_from_right(&[0x1u64]);
+        let num4 = BigUInt4096::new(&[u64::MAX; 63]);
+        assert!(num3 > num4);
+        assert_eq!(num3.cmp(&num3.clone()), std::cmp::Ordering::Equal);
+        assert!(BigUInt4096::default() < BigUInt4096::new(&[0x1u64]));
+    }
+
+    // test min, max and clamp, which come with the ordering
+    #[test]
+    fn min_max_clamp_test() {
+        let low = BigUInt4096::new(&[u64::MAX]);
+        let mid = BigUInt4096::new(&[0x0u64, 0x1u64]);
+        let high = BigUInt4096::new(&[0x0u64, 0x0u64, 0x1u64]);
+        assert_eq!(low.clone().min(high.clone()), low);
+        assert_eq!(low.clone().max(high.clone()), high);
+        assert_eq!(high.clone().clamp(low.clone(), mid.clone()), mid);
+        assert_eq!(BigUInt4096::default().clamp(low.clone(), mid.clone()), low);
+        let mut nums = vec![high.clone(), low.clone(), mid.clone()];
+        nums.sort();
+        assert_eq!(nums, vec![low, mid, high]);
+    }
+
+    #[test]
+    fn is_zero_is_one_test() {
+        assert!(BigUInt4096::default().is_zero());
+        assert!(!BigUInt4096::new_from_right(&[0x1u64]).is_zero());
+        assert!(BigUInt4096::new(&[0x1u64]).is_one());
+        assert!(!BigUInt4096::new(&[0x1u64, 0x1u64]).is_one());
+        assert!(!BigUInt4096::default().is_one());
+    }
+
+    // test the checked, wrapping and saturating families without overflow
+    #[test]
+    fn checked_wrapping_saturating_test1() {
+        let num1 = BigUInt4096::new(&[u64::MAX]);
+        let num2 = BigUInt4096::new(&[0x2u64]);
+        let sum = BigUInt4096::new(&[0x1u64, 0x1u64]);
+        let diff = BigUInt4096::new(&[u64::MAX - 2]);
+        let prod = BigUInt4096::new(&[u64::MAX - 1, 0x1u64]);
+        assert_eq!(num1.checked_add(&num2), Some(sum.clone()));
+        assert_eq!(num1.wrapping_add(&num2), sum);
+        assert_eq!(num1.saturating_add(&num2), sum);
+        assert_eq!(num1.checked_sub(&num2), Some(diff.clone()));
+        assert_eq!(num1.wrapping_sub(&num2), diff);
+        assert_eq!(num1.saturating_sub(&num2), diff);
+        assert_eq!(num1.checked_mul(&num2), Some(prod.clone()));
+        assert_eq!(num1.wrapping_mul(&num2), prod);
+        assert_eq!(num1.saturating_mul(&num2), prod);
+        assert_eq!(
+            num1.checked_div(&num2),
+            Some(BigUInt4096::new(&[u64::MAX / 2]))
+        );
+        assert_eq!(num1.checked_rem(&num2), Some(BigUInt4096::new(&[0x1u64])));
+    }
+
+    // test the checked, wrapping and saturating families with overflow
+    #[test]
+    fn checked_wrapping_saturating_test2() {
+        let max = BigUInt256::new(&[u64::MAX; 4]);
+        let num1 = BigUInt256::new_from_right(&[0x1u64, 0x0u64]);
+        let num2 = BigUInt256::new(&[0x2u64]);
+        assert_eq!(max.checked_add(&num2), None);
+        assert_eq!(max.wrapping_add(&num2), BigUInt256::new(&[0x1u64]));
+        assert_eq!(max.saturating_add(&num2), max);
+        assert_eq!(num2.checked_sub(&max), None);
+        assert_eq!(num2.wrapping_sub(&max), BigUInt256::new(&[0x3u64]));
+        assert_eq!(num2.saturating_sub(&max), BigUInt256::default());
+        assert_eq!(num1.checked_mul(&num1), None);
+        assert_eq!(num1.wrapping_mul(&num1), BigUInt256::default());
+        assert_eq!(num1.saturating_mul(&num1), max);
+        assert_eq!(
+            max.overflowing_mul(&num2),
+            (max.wrapping_sub(&BigUInt256::new(&[0x1u64])), true)
+        );
+        assert_eq!(num2.checked_div(&BigUInt256::default()), None);
+        assert_eq!(num2.checked_rem(&BigUInt256::default()), None);
+    }
+
+    #[test]
+    fn add_assign_test() {
+        let mut num1 = BigUInt4096::new(&[2u64, 0u64]);
+        num1 += BigUInt4096::new(&[u64::MAX]);
+        assert_eq!(num1, BigUInt4096::new(&[1u64, 1u64]));
+        num1 += &BigUInt4096::new(&[u64::MAX]);
+        assert_eq!(num1, BigUInt4096::new(&[0u64, 2u64]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_assign_overflow_test() {
+        let mut num1 = BigUInt4096::new_from_right(&[u64::MAX]);
+        num1 += BigUInt4096::new_from_right(&[1u64]);
+    }
+
+    #[test]
+    fn sub_assign_test() {
+        let mut num1 = BigUInt4096::new(&[0u64, 4u64]);
+        num1 -= BigUInt4096::new(&[u64::MAX]);
+        assert_eq!(num1, BigUInt4096::new(&[1u64, 3u64]));
+        num1 -= &BigUInt4096::new(&[2u64, 2u64]);
+        assert_eq!(num1, BigUInt4096::new(&[u64::MAX]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_assign_overflow_test() {
+        let mut num1 = BigUInt4096::new(&[1u64]);
+        num1 -= BigUInt4096::new(&[2u64]);
+    }
+
+    #[test]
+    fn mul_assign_test() {
+        let mut num1 = BigUInt4096::new_from_middle(&[0x1u64, 0x1u64], 20);
+        num1 *= BigUInt4096::new_from_middle(&[0x1u64, 0x1u64], 32);
+        assert_eq!(
+            num1,
+            BigUInt4096::new_from_middle(&[0x1u64, 0x2u64, 0x1u64], 52)
+        );
+        num1 *= &BigUInt4096::new(&[0x3u64]);
+        assert_eq!(
+            num1,
+            BigUInt4096::new_from_middle(&[0x3u64, 0x6u64, 0x3u64], 52)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_assign_overflow_test() {
+        let mut num1 = BigUInt4096::new_from_middle(&[0x7fu64], 40);
+        num1 *= BigUInt4096::new_from_middle(&[0xeeu64], 34);
+    }
+
+    #[test]
+    fn div_rem_assign_test() {
+        let mut num1 = BigUInt4096::new(&[0x1u64, 0xfedcba9876543210u64, 0x1u64]);
+        let mut num2 = num1.clone();
+        num1 /= BigUInt4096::new(&[0x1000000000000003u64]);
+        assert_eq!(num1, BigUInt4096::new(&[0xedcba98765431b03u64, 0x1fu64]));
+        num2 %= &BigUInt4096::new(&[0x1000000000000003u64]);
+        assert_eq!(num2, BigUInt4096::new(&[0x69d0369d036aef8u64]));
+    }
+
+    #[test]
+    fn sum_product_test() {
+        let nums = vec![
+            BigUInt4096::new(&[u64::MAX]),
+            BigUInt4096::new(&[0x2u64]),
+            BigUInt4096::new(&[0x0u64, 0x1u64]),
+        ];
+        let sum = BigUInt4096::new(&[0x1u64, 0x2u64]);
+        let prod = BigUInt4096::new(&[0x0u64, u64::MAX - 1, 0x1u64]);
+        assert_eq!(nums.iter().sum::<BigUInt4096>(), sum);
+        assert_eq!(nums.clone().into_iter().sum::<BigUInt4096>(), sum);
+        assert_eq!(nums.iter().product::<BigUInt4096>(), prod);
+        assert_eq!(nums.into_iter().product::<BigUInt4096>(), prod);
+        assert_eq!(
+            Vec::<BigUInt4096>::new().iter().sum::<BigUInt4096>(),
+            BigUInt4096::default()
+        );
+        assert!(Vec::<BigUInt4096>::new()
+            .iter()
+            .product::<BigUInt4096>()
+            .is_one());
+    }
+
+    // test that the significant length is a tight bound after each operation, and that results
+    // match those on the same operands taken at full width
+    #[test]
+    fn significant_len_test() {
+        let mut rng = TestRng(0x5eed);
+        let full = |num: &BigUInt4096| BigUInt::from_chunks_full(num.chunks);
+        let tight = |num: &BigUInt4096| num.len == significant_chunks(&num.chunks);
+        for bits in [0, 1, 64, 65, 500, 2047, 2048, 4095, 4096] {
+            for other_bits in [0, 1, 63, 64, 129, 2048, 4096] {
+                let num1 = BigUInt4096::random_bits(bits, &mut rng);
+                let num2 = BigUInt4096::random_bits(other_bits, &mut rng);
+                let (num1_full, num2_full) = (full(&num1), full(&num2));
+                assert!(tight(&num1) && tight(&num2));
+                let sum = num1.overflowing_add(&num2);
+                assert!(tight(&sum.0));
+                assert_eq!(sum, num1_full.overflowing_add(&num2_full));
+                let diff = num1.overflowing_sub(&num2);
+                assert!(tight(&diff.0));
+                assert_eq!(diff, num1_full.overflowing_sub(&num2_full));
+                let prod = num1.overflowing_mul(&num2);
+                assert!(tight(&prod.0));
+                assert_eq!(prod, num1_full.overflowing_mul(&num2_full));
+                let wide: BigUInt8192 = num1.widening_mul(&num2);
+                assert_eq!(wide.len, significant_chunks(&wide.chunks));
+                assert_eq!(wide, num1_full.widening_mul(&num2_full));
+                let (low, high) = wide.split::<64>();
+                assert!(tight(&low) && tight(&high));
+                assert_eq!(num1.cmp(&num2), num1_full.cmp(&num2_full));
+                if let Some((quot, rem)) = num1.checked_div_rem(&num2) {
+                    assert!(tight(&quot) && tight(&rem));
+                    assert_eq!((quot, rem), num1_full.div_rem(&num2_full));
+                }
+                assert!(tight(&(&num1 << 70)) && tight(&(&num1 >> 70)));
+                assert!(tight(&(&num1 ^ &num1)) && tight(&!&num1));
+            }
+        }
+    }
 }
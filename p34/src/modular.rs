@@ -0,0 +1,428 @@
+//! Arithmetic modulo a fixed `BigUInt4096` by Barrett reduction, with double-width
+//! intermediates in `BigUInt8192`, and Chinese Remainder Theorem recombination.
+
+use crate::{BigUInt4096, BigUInt8192};
+
+/// modulus with precomputed Barrett constants
+///
+/// for an `n`-bit modulus `m`, `mu = floor((2^(2n) - 1) / m)` lies in `[2^n, 2^(n + 1))`;
+/// only `mu - 2^n` is stored so that every product fits in 8192 bits, even for n = 4096
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Modulus {
+    modulus: BigUInt4096,
+    bits: u32,
+    mu_low: BigUInt8192,
+}
+
+/// value reduced modulo a `Modulus`; arithmetic between values of different moduli panics
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModInt<'a> {
+    value: BigUInt4096,
+    modulus: &'a Modulus,
+}
+
+fn widen(value: &BigUInt4096) -> BigUInt8192 {
+    BigUInt8192::from_halves(value, &BigUInt4096::default())
+}
+
+impl Modulus {
+    /// returns `None` for a zero modulus
+    pub fn new(modulus: &BigUInt4096) -> Option<Self> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let bits = modulus.bits();
+        let all_ones = !BigUInt8192::default() >> (8192 - 2 * bits);
+        let mu = all_ones / widen(modulus);
+        let mu_low = mu - (BigUInt8192::new(&[1u64]) << bits);
+        Some(Modulus {
+            modulus: modulus.clone(),
+            bits,
+            mu_low,
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUInt4096 {
+        &self.modulus
+    }
+
+    /// Barrett reduction of `value < 2^(2n)`: the estimate `q = floor(floor(value / 2^n) * mu / 2^n)`
+    /// undershoots the quotient by a small amount, fixed by a few final subtractions
+    pub fn reduce(&self, value: &BigUInt8192) -> BigUInt4096 {
+        debug_assert!(value.bits() <= 2 * self.bits, "Value too large to reduce");
+        let q1 = value >> self.bits;
+        // q1 * mu = q1 * 2^n + q1 * mu_low
+        let quot = &q1 + ((&q1 * &self.mu_low) >> self.bits);
+        let modulus = widen(&self.modulus);
+        let mut rem = value - quot * &modulus;
+        while rem >= modulus {
+            rem -= &modulus;
+        }
+        rem.split().0
+    }
+
+    /// reduces an arbitrary value into the range of the modulus
+    pub fn int(&self, value: &BigUInt4096) -> ModInt<'_> {
+        let value = if value.bits() <= 2 * self.bits {
+            self.reduce(&widen(value))
+        } else {
+            value % &self.modulus
+        };
+        ModInt {
+            value,
+            modulus: self,
+        }
+    }
+
+    pub fn zero(&self) -> ModInt<'_> {
+        self.int(&BigUInt4096::default())
+    }
+
+    pub fn one(&self) -> ModInt<'_> {
+        self.int(&BigUInt4096::new(&[1u64]))
+    }
+}
+
+impl<'a> ModInt<'a> {
+    /// reduced value, in `[0, modulus)`
+    pub fn value(&self) -> &BigUInt4096 {
+        &self.value
+    }
+
+    pub fn modulus(&self) -> &'a Modulus {
+        self.modulus
+    }
+
+    fn with_value(&self, value: BigUInt4096) -> Self {
+        ModInt {
+            value,
+            modulus: self.modulus,
+        }
+    }
+
+    fn check_modulus(&self, other: &Self) {
+        assert!(
+            std::ptr::eq(self.modulus, other.modulus) || self.modulus == other.modulus,
+            "Mismatched moduli"
+        );
+    }
+
+    fn add_mod(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        let modulus = &self.modulus.modulus;
+        let (sum, carry) = self.value.overflowing_add(&other.value);
+        if carry || &sum >= modulus {
+            self.with_value(sum.wrapping_sub(modulus))
+        } else {
+            self.with_value(sum)
+        }
+    }
+
+    fn sub_mod(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        match self.value.overflowing_sub(&other.value) {
+            (diff, true) => self.with_value(diff.wrapping_add(&self.modulus.modulus)),
+            (diff, _) => self.with_value(diff),
+        }
+    }
+
+    fn mul_mod(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        let prod: BigUInt8192 = self.value.widening_mul(&other.value);
+        self.with_value(self.modulus.reduce(&prod))
+    }
+
+    fn neg_mod(&self) -> Self {
+        if self.value.is_zero() {
+            self.clone()
+        } else {
+            self.with_value(&self.modulus.modulus - &self.value)
+        }
+    }
+
+    /// `self^exponent` by left-to-right square-and-multiply
+    pub fn pow(&self, exponent: &BigUInt4096) -> Self {
+        let mut result = self.modulus.one();
+        for i in (0..exponent.bits()).rev() {
+            result = &result * &result;
+            if exponent.bit(i) {
+                result = &result * self;
+            }
+        }
+        result
+    }
+
+    /// multiplicative inverse, or `None` if the value is not coprime to the modulus
+    pub fn inv(&self) -> Option<Self> {
+        self.value
+            .mod_inverse(&self.modulus.modulus)
+            .map(|value| self.with_value(value))
+    }
+}
+
+/// combines residues `r_i mod m_i` for pairwise coprime moduli into the unique value modulo
+/// their product, by Garner's method; returns the value and the product, or `None` if the
+/// moduli are zero or not coprime, or the product does not fit in 4096 bits
+pub fn crt(residues: &[(BigUInt4096, BigUInt4096)]) -> Option<(BigUInt4096, BigUInt4096)> {
+    let mut value = BigUInt4096::default();
+    let mut product = BigUInt4096::new(&[1u64]);
+    for (residue, modulus) in residues {
+        let context = Modulus::new(modulus)?;
+        // value + product * t is congruent to residue modulo m for t = (residue - value) / product
+        let diff = context.int(residue) - context.int(&value);
+        let t = diff * context.int(&product).inv()?;
+        let next_product = product.checked_mul(modulus)?;
+        value += &product * t.value();
+        product = next_product;
+    }
+    Some((value, product))
+}
+
+impl<'a> std::ops::Add<ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn add(self, other: ModInt<'a>) -> Self::Output {
+        self.add_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Add<&ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn add(self, other: &ModInt<'a>) -> Self::Output {
+        self.add_mod(other)
+    }
+}
+
+impl<'a> std::ops::Add<ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn add(self, other: ModInt<'a>) -> Self::Output {
+        self.add_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Add<&ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn add(self, other: &ModInt<'a>) -> Self::Output {
+        self.add_mod(other)
+    }
+}
+
+impl<'a> std::ops::Sub<ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn sub(self, other: ModInt<'a>) -> Self::Output {
+        self.sub_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Sub<&ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn sub(self, other: &ModInt<'a>) -> Self::Output {
+        self.sub_mod(other)
+    }
+}
+
+impl<'a> std::ops::Sub<ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn sub(self, other: ModInt<'a>) -> Self::Output {
+        self.sub_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Sub<&ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn sub(self, other: &ModInt<'a>) -> Self::Output {
+        self.sub_mod(other)
+    }
+}
+
+impl<'a> std::ops::Mul<ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn mul(self, other: ModInt<'a>) -> Self::Output {
+        self.mul_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Mul<&ModInt<'a>> for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn mul(self, other: &ModInt<'a>) -> Self::Output {
+        self.mul_mod(other)
+    }
+}
+
+impl<'a> std::ops::Mul<ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn mul(self, other: ModInt<'a>) -> Self::Output {
+        self.mul_mod(&other)
+    }
+}
+
+impl<'a> std::ops::Mul<&ModInt<'a>> for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn mul(self, other: &ModInt<'a>) -> Self::Output {
+        self.mul_mod(other)
+    }
+}
+
+impl<'a> std::ops::Neg for ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn neg(self) -> Self::Output {
+        self.neg_mod()
+    }
+}
+
+impl<'a> std::ops::Neg for &ModInt<'a> {
+    type Output = ModInt<'a>;
+
+    fn neg(self) -> Self::Output {
+        self.neg_mod()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, Modulus};
+    use crate::{BigUInt4096, BigUInt8192};
+
+    // test Barrett reduction against division for moduli of many sizes, including full width
+    // and powers of two, where the stored constant is one below the exact Barrett constant
+    #[test]
+    fn reduce_test() {
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let one = BigUInt4096::new(&[1u64]);
+        let mut moduli = vec![one.clone(), BigUInt4096::new(&[2u64]), one.clone() << 4095];
+        moduli.push(!BigUInt4096::default());
+        for limbs in [1, 2, 17, 63, 64] {
+            let chunks: Vec<u64> = (0..limbs).map(|_| next()).collect();
+            moduli.push(BigUInt4096::new(&chunks) >> (next() % 64) as u32 | &one);
+        }
+        for modulus in moduli {
+            let context = Modulus::new(&modulus).unwrap();
+            let wide_modulus = BigUInt8192::from_halves(&modulus, &BigUInt4096::default());
+            for _ in 0..10 {
+                let a = BigUInt4096::random_below(&modulus, &mut crate::random::TestRng(next()));
+                let b = BigUInt4096::random_below(&modulus, &mut crate::random::TestRng(next()));
+                let prod: BigUInt8192 = a.widening_mul(&b);
+                assert_eq!(context.reduce(&prod), (&prod % &wide_modulus).split().0);
+            }
+            let max_prod: BigUInt8192 = (&modulus - &one).widening_mul(&(&modulus - &one));
+            assert_eq!(
+                context.reduce(&max_prod),
+                (&max_prod % &wide_modulus).split().0
+            );
+        }
+        assert_eq!(Modulus::new(&BigUInt4096::default()), None);
+    }
+
+    // test modular operators against BigUInt arithmetic, including wrap-around at full width
+    #[test]
+    fn ops_test() {
+        let small = |n: u64| BigUInt4096::new(&[n]);
+        let context = Modulus::new(&small(17)).unwrap();
+        for a in 0..17 {
+            for b in 0..17 {
+                let (x, y) = (context.int(&small(a)), context.int(&small(b)));
+                assert_eq!((x.clone() + y.clone()).value(), &small((a + b) % 17));
+                assert_eq!((x.clone() - &y).value(), &small((a + 17 - b) % 17));
+                assert_eq!((&x * y.clone()).value(), &small(a * b % 17));
+                assert_eq!((&x + &y - &y), x);
+            }
+            assert_eq!((-context.int(&small(a))).value(), &small((17 - a) % 17));
+        }
+        assert_eq!(context.int(&small(40)).value(), &small(6));
+        let large = !BigUInt4096::default() - small(188);
+        let context = Modulus::new(&large).unwrap();
+        let x = context.int(&(&large - small(1)));
+        assert_eq!((&x + &x).value(), &(&large - small(2)));
+        assert_eq!((&x * &x).value(), &small(1));
+        assert_eq!((-&x).value(), &small(1));
+        assert_eq!(context.int(&!BigUInt4096::default()).value(), &small(188));
+    }
+
+    // test pow against modpow and inv against mod_inverse
+    #[test]
+    fn pow_inv_test() {
+        let mut rng = crate::random::TestRng(0x9e3779b97f4a7c15);
+        let one = BigUInt4096::new(&[1u64]);
+        let odd = BigUInt4096::random_bits(1000, &mut rng) | &one;
+        let even = BigUInt4096::random_bits(700, &mut rng) << 3;
+        for modulus in [odd, even] {
+            let context = Modulus::new(&modulus).unwrap();
+            let base = BigUInt4096::random_below(&modulus, &mut rng);
+            let exponent = BigUInt4096::random_bits(300, &mut rng);
+            let power = context.int(&base).pow(&exponent);
+            if modulus.bit(0) {
+                assert_eq!(power.value(), &base.modpow(&exponent, &modulus));
+            }
+            // a^(e1 + e2) = a^e1 * a^e2
+            let other = BigUInt4096::random_bits(200, &mut rng);
+            assert_eq!(
+                context.int(&base).pow(&(&exponent + &other)),
+                power * context.int(&base).pow(&other)
+            );
+            assert_eq!(
+                context.int(&base).pow(&BigUInt4096::default()),
+                context.one()
+            );
+            let value = context.int(&(BigUInt4096::random_below(&modulus, &mut rng) | &one));
+            match value.inv() {
+                Some(inverse) => assert_eq!(&value * &inverse, context.one()),
+                None => assert!(!value.value().gcd(&modulus).is_one()),
+            }
+            assert_eq!(context.zero().inv(), None);
+        }
+    }
+
+    // test operations on values of different moduli panic
+    #[test]
+    #[should_panic(expected = "Mismatched moduli")]
+    fn mismatched_moduli_test() {
+        let one = Modulus::new(&BigUInt4096::new(&[7u64])).unwrap();
+        let other = Modulus::new(&BigUInt4096::new(&[11u64])).unwrap();
+        let _ = one.int(&BigUInt4096::new(&[3u64])) + other.int(&BigUInt4096::new(&[3u64]));
+    }
+
+    // test CRT recombination, including non-coprime moduli and an oversized product
+    #[test]
+    fn crt_test() {
+        let small = |n: u64| BigUInt4096::new(&[n]);
+        let residues = [
+            (small(2), small(3)),
+            (small(3), small(5)),
+            (small(2), small(7)),
+        ];
+        assert_eq!(crt(&residues), Some((small(23), small(105))));
+        assert_eq!(crt(&[]), Some((small(0), small(1))));
+        assert_eq!(crt(&[(small(1), small(4)), (small(3), small(6))]), None);
+        assert_eq!(crt(&[(small(1), small(0))]), None);
+        // residues modulo the Mersenne primes 2^521 - 1 and 2^607 - 1
+        let mut rng = crate::random::TestRng(0x9e3779b97f4a7c15);
+        let p = (small(1) << 521) - small(1);
+        let q = (small(1) << 607) - small(1);
+        let value = BigUInt4096::random_below(&(&p * &q), &mut rng);
+        let residues = [(&value % &p, p.clone()), (&value % &q, q.clone())];
+        assert_eq!(crt(&residues), Some((value, &p * &q)));
+        let big = (BigUInt4096::new(&[1u64]) << 3000) + small(1);
+        assert_eq!(
+            crt(&[(small(1), big.clone()), (small(1), big - small(2))]),
+            None
+        );
+    }
+}
@@ -0,0 +1,479 @@
+use crate::ct::{ct_mul_chunks, step};
+use crate::{significant_chunks, BigUInt};
+use subtle::Choice;
+
+/// precomputed constants for arithmetic in Montgomery form modulo a fixed odd modulus,
+/// with Montgomery radix R = 2^(64 * LIMBS)
+#[derive(Clone, Debug)]
+pub struct MontgomeryContext<const LIMBS: usize> {
+    modulus: BigUInt<LIMBS>,
+    // -modulus^(-1) mod 2^64
+    m_inv: u64,
+    // R mod modulus, i.e. 1 in Montgomery form
+    r1: BigUInt<LIMBS>,
+    // R^2 mod modulus, used to move values into Montgomery form
+    r2: BigUInt<LIMBS>,
+}
+
+impl<const LIMBS: usize> MontgomeryContext<LIMBS> {
+    /// returns `None` if `modulus` is even, since Montgomery reduction needs it to be odd
+    pub fn new(modulus: &BigUInt<LIMBS>) -> Option<Self> {
+        if modulus.chunks[0] & 1 == 0 {
+            return None;
+        }
+        // Newton iteration for the inverse mod 2^64; each step doubles the number of correct bits
+        let mut inv = modulus.chunks[0];
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.chunks[0].wrapping_mul(inv)));
+        }
+        // R mod modulus = (R - modulus) mod modulus
+        let (r1, _) = BigUInt::default().overflowing_sub(modulus);
+        let r1 = r1 % modulus;
+        // R^2 mod modulus by doubling R mod modulus another 64 * LIMBS times
+        let mut r2 = r1.clone();
+        for _ in 0..64 * LIMBS {
+            let (double, carry) = r2.overflowing_add(&r2);
+            r2 = Self::reduce_once(double, carry, modulus);
+        }
+        Some(Self {
+            modulus: modulus.clone(),
+            m_inv: inv.wrapping_neg(),
+            r1,
+            r2,
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUInt<LIMBS> {
+        &self.modulus
+    }
+
+    /// subtracts the modulus from `value` (plus `carry` times R) if that is at least the modulus
+    fn reduce_once(value: BigUInt<LIMBS>, carry: bool, modulus: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        let (diff, borrow) = value.overflowing_sub(modulus);
+        if carry || !borrow {
+            diff
+        } else {
+            value
+        }
+    }
+
+    /// Montgomery reduction: returns `prod * R^(-1) mod modulus` for `prod < modulus * R`
+    fn redc(&self, mut prod: [[u64; LIMBS]; 2]) -> BigUInt<LIMBS> {
+        let chunks = prod.as_flattened_mut();
+        let mut overflow = false;
+        for i in 0..LIMBS {
+            // add a multiple of the modulus that clears chunk i
+            let factor = chunks[i].wrapping_mul(self.m_inv) as u128;
+            let mut carry: u64 = 0;
+            for j in 0..LIMBS {
+                let acc =
+                    chunks[i + j] as u128 + factor * self.modulus.chunks[j] as u128 + carry as u128;
+                chunks[i + j] = acc as u64;
+                carry = (acc >> 64) as u64;
+            }
+            // then ripple the final carry upwards
+            let mut ripple: bool;
+            (chunks[i + LIMBS], ripple) = chunks[i + LIMBS].overflowing_add(carry);
+            let mut k = i + LIMBS + 1;
+            while ripple && k < 2 * LIMBS {
+                (chunks[k], ripple) = chunks[k].overflowing_add(1);
+                k += 1;
+            }
+            overflow = overflow || ripple;
+        }
+        let [_, high] = prod;
+        Self::reduce_once(BigUInt::from_chunks(high), overflow, &self.modulus)
+    }
+
+    /// Montgomery product: returns `one * other * R^(-1) mod modulus`
+    pub fn mul(&self, one: &BigUInt<LIMBS>, other: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        self.redc(one.full_mul(other))
+    }
+
+    /// converts `value` into Montgomery form
+    pub fn to_montgomery(&self, value: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        self.mul(value, &self.r2)
+    }
+
+    /// converts `value` out of Montgomery form
+    pub fn from_montgomery(&self, value: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        self.redc([value.chunks, [0u64; LIMBS]])
+    }
+
+    /// returns `base^exponent mod modulus` by left-to-right square-and-multiply in Montgomery form
+    pub fn modpow(&self, base: &BigUInt<LIMBS>, exponent: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        let base = self.to_montgomery(base);
+        let mut result = self.r1.clone();
+        for i in (0..significant_chunks(exponent.digits())).rev() {
+            for bit in (0..64).rev() {
+                result = self.mul(&result, &result);
+                if (exponent.chunks[i] >> bit) & 1 == 1 {
+                    result = self.mul(&result, &base);
+                }
+            }
+        }
+        self.from_montgomery(&result)
+    }
+}
+
+impl<const LIMBS: usize> MontgomeryContext<LIMBS> {
+    /// constant-time Montgomery reduction, as `redc` but without data-dependent branches
+    fn ct_redc(&self, mut prod: [[u64; LIMBS]; 2]) -> BigUInt<LIMBS> {
+        let chunks = prod.as_flattened_mut();
+        let mut overflow: u64 = 0;
+        for i in 0..LIMBS {
+            // add a multiple of the modulus that clears chunk i
+            let factor = chunks[i].wrapping_mul(self.m_inv) as u128;
+            let mut carry: u64 = 0;
+            for j in 0..LIMBS {
+                step(9);
+                let acc =
+                    chunks[i + j] as u128 + factor * self.modulus.chunks[j] as u128 + carry as u128;
+                chunks[i + j] = acc as u64;
+                carry = (acc >> 64) as u64;
+            }
+            // then ripple the final carry through every chunk above, without stopping early
+            for chunk in chunks[i + LIMBS..].iter_mut() {
+                step(10);
+                let acc = *chunk as u128 + carry as u128;
+                *chunk = acc as u64;
+                carry = (acc >> 64) as u64;
+            }
+            overflow |= carry;
+        }
+        let [_, high] = prod;
+        let high = BigUInt::from_chunks_full(high);
+        // subtract the modulus once if the sum overflowed R or does not borrow
+        let (diff, borrow) = high.ct_sub(&self.modulus);
+        let reduce = overflow as u8 | (borrow.unwrap_u8() ^ 1);
+        BigUInt::conditional_select(&high, &diff, Choice::from(reduce))
+    }
+
+    /// constant-time Montgomery product
+    pub fn ct_mul(&self, one: &BigUInt<LIMBS>, other: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        let mut prod = [[0u64; LIMBS]; 2];
+        ct_mul_chunks(&one.chunks, &other.chunks, prod.as_flattened_mut());
+        self.ct_redc(prod)
+    }
+
+    /// constant-time `modpow`: runs over every bit of the exponent's width, always computes
+    /// the multiplication and keeps it by masked selection
+    pub fn ct_modpow(&self, base: &BigUInt<LIMBS>, exponent: &BigUInt<LIMBS>) -> BigUInt<LIMBS> {
+        let base = self.ct_mul(base, &self.r2);
+        let mut result = self.r1.clone();
+        for i in (0..LIMBS).rev() {
+            for bit in (0..64).rev() {
+                result = self.ct_mul(&result, &result);
+                let prod = self.ct_mul(&result, &base);
+                let choice = Choice::from(((exponent.chunks[i] >> bit) & 1) as u8);
+                result.conditional_assign(&prod, choice);
+            }
+        }
+        self.ct_redc([result.chunks, [0u64; LIMBS]])
+    }
+}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// returns `self^exponent mod modulus`, panicking if `modulus` is even
+    pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Self {
+        MontgomeryContext::new(modulus)
+            .expect("Modulus must be odd")
+            .modpow(self, exponent)
+    }
+
+    /// constant-time `modpow` for a secret base and exponent; the modulus is treated as public
+    /// and must be odd
+    pub fn ct_modpow(&self, exponent: &Self, modulus: &Self) -> Self {
+        MontgomeryContext::new(modulus)
+            .expect("Modulus must be odd")
+            .ct_modpow(self, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ct::trace;
+    use crate::montgomery::MontgomeryContext;
+    use crate::{BigUInt1024, BigUInt256, BigUInt4096};
+
+    // test modpow with single-chunk values
+    #[test]
+    fn modpow_test1() {
+        let base = BigUInt4096::new(&[4u64]);
+        let exponent = BigUInt4096::new(&[13u64]);
+        let modulus = BigUInt4096::new(&[497u64]);
+        assert_eq!(
+            base.modpow(&exponent, &modulus),
+            BigUInt4096::new(&[445u64])
+        );
+    }
+
+    // test modpow with 1024-bit values
+    #[test]
+    fn modpow_test2() {
+        let base = BigUInt4096::new(&[
+            0x72ff5d2a386ecbe0u64,
+            0x4737819096da1dacu64,
+            0xde8a774bcf36d58bu64,
+            0xc241330b01a9e71fu64,
+            0x28df6ec4ce4a2bbdu64,
+            0x6c307511b2b9437au64,
+            0x47229389571aa876u64,
+            0x371ecd7b27cd8130u64,
+            0xc37459eef50bea63u64,
+            0x1a2a73ed562b0f79u64,
+            0x6142ea7d17be3111u64,
+            0x5be6128e18c26797u64,
+            0x580d7b71d8f56413u64,
+            0x43b7a3a69a8dca03u64,
+            0xb1f9163ce9ff57fu64,
+            0x759cde66bacfb3d0u64,
+        ]);
+        let exponent = BigUInt4096::new(&[
+            0x1ff49b7889463e85u64,
+            0xec1b8ca1f91e1d4cu64,
+            0x142c3fe860e7a113u64,
+            0x4b0dbb418d5288f1u64,
+            0xa0ee89aed453dd32u64,
+            0xe2acf72f9e574f7au64,
+            0x5c941cf0dc98d2c1u64,
+            0x3139d32c93cd59bfu64,
+            0x11ce5dd2b45ed1f0u64,
+            0xa9488d990bbb2599u64,
+            0xc5e7ce8a3a578a8eu64,
+            0xfc377a4c4a15544du64,
+            0xdaf61a26146d3f31u64,
+            0xddd1dfb23b982ef8u64,
+            0x614ff3d719db3ad0u64,
+            0x7412b29347294739u64,
+        ]);
+        let modulus = BigUInt4096::new(&[
+            0x1c80317fa3b1799du64,
+            0xbdd640fb06671ad1u64,
+            0x3eb13b9046685257u64,
+            0x23b8c1e9392456deu64,
+            0x1a3d1fa7bc8960a9u64,
+            0xbd9c66b3ad3c2d6du64,
+            0x8b9d2434e465e150u64,
+            0x972a846916419f82u64,
+            0x822e8f36c031199u64,
+            0x17fc695a07a0ca6eu64,
+            0x3b8faa1837f8a88bu64,
+            0x9a1de644815ef6d1u64,
+            0x8fadc1a606cb0fb3u64,
+            0xb74d0fb132e70629u64,
+            0xb38a088ca65ed389u64,
+            0xeb65a6a48b8148f6u64,
+        ]);
+        let result = BigUInt4096::new(&[
+            0x23fee8be09f15e5fu64,
+            0x1e5a024d65905543u64,
+            0xca7fa17e80f131dbu64,
+            0x1db17066596279a6u64,
+            0x7dd2da756902507cu64,
+            0xabe28a1bfe85dccau64,
+            0xec7ac28e56a4a844u64,
+            0x465b52d26c9ce338u64,
+            0xced4cf986f58f7abu64,
+            0x4d5d7a8e73268aeu64,
+            0x9c95e22cfe30535fu64,
+            0x3ab70db4629134dfu64,
+            0x3e9f1fd0202446fbu64,
+            0xd5bc035e4af3a357u64,
+            0xa5816eba24cca5a4u64,
+            0xd4b1189a706ae820u64,
+        ]);
+        assert_eq!(base.modpow(&exponent, &modulus), result);
+    }
+
+    // test Fermat's little theorem for the Mersenne prime 2^521 - 1
+    #[test]
+    fn modpow_test3() {
+        let mut chunks = [u64::MAX; 9];
+        chunks[8] = 0x1ff;
+        let modulus = BigUInt4096::new(&chunks);
+        chunks[0] -= 1;
+        let exponent = BigUInt4096::new(&chunks);
+        let base = BigUInt4096::new(&[3u64]);
+        assert_eq!(base.modpow(&exponent, &modulus), BigUInt4096::new(&[1u64]));
+    }
+
+    // test modpow modulo 2^4096 - 1, where 2^4096 is congruent to 1
+    #[test]
+    fn modpow_test4() {
+        let modulus = BigUInt4096::new(&[u64::MAX; 64]);
+        let base = BigUInt4096::new(&[2u64]);
+        let exponent = BigUInt4096::new(&[4096 * 3 + 5]);
+        assert_eq!(base.modpow(&exponent, &modulus), BigUInt4096::new(&[32u64]));
+    }
+
+    // test modpow with full-width operands: (-1)^odd is -1 modulo 2^4096 - 1
+    #[test]
+    fn modpow_test5() {
+        let modulus = BigUInt4096::new(&[u64::MAX; 64]);
+        let mut chunks = [u64::MAX; 64];
+        chunks[0] -= 1;
+        let base = BigUInt4096::new(&chunks);
+        assert_eq!(base.modpow(&modulus, &modulus), base);
+    }
+
+    // test modpow with a zero exponent and with a base larger than the modulus
+    #[test]
+    fn modpow_test6() {
+        let modulus = BigUInt4096::new(&[0x1u64, 0x1u64]);
+        let base = BigUInt4096::new_from_right(&[0x5u64]);
+        assert_eq!(
+            base.modpow(&BigUInt4096::default(), &modulus),
+            BigUInt4096::new(&[1u64])
+        );
+        assert_eq!(
+            base.modpow(&BigUInt4096::new(&[1u64]), &modulus),
+            &base % &modulus
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn modpow_test7() {
+        let modulus = BigUInt4096::new(&[0x10u64]);
+        let base = BigUInt4096::new(&[0x3u64]);
+        println!("{:?}", base.modpow(&base, &modulus));
+    }
+
+    // test conversion in and out of Montgomery form
+    #[test]
+    fn montgomery_form_test() {
+        let modulus = BigUInt4096::new(&[0x1c80317fa3b1799du64, 0xbdd640fb06671ad1u64]);
+        let ctx = MontgomeryContext::new(&modulus).unwrap();
+        let value = BigUInt4096::new(&[0x1234u64, 0x5678u64]);
+        let mont = ctx.to_montgomery(&value);
+        assert_ne!(mont, value);
+        assert_eq!(ctx.from_montgomery(&mont), value);
+        assert!(MontgomeryContext::new(&BigUInt4096::new(&[0x10u64])).is_none());
+    }
+
+    // test modpow at a narrower width, where R is 2^256
+    #[test]
+    fn modpow_256_test() {
+        let modulus = BigUInt256::new(&[u64::MAX; 4]);
+        let base = BigUInt256::new(&[2u64]);
+        let exponent = BigUInt256::new(&[256 * 5 + 7]);
+        assert_eq!(base.modpow(&exponent, &modulus), BigUInt256::new(&[128u64]));
+    }
+
+    // test constant-time modpow against the 1024-bit known answer
+    #[test]
+    fn ct_modpow_test1() {
+        let base = BigUInt1024::new(&[
+            0x72ff5d2a386ecbe0u64,
+            0x4737819096da1dacu64,
+            0xde8a774bcf36d58bu64,
+            0xc241330b01a9e71fu64,
+            0x28df6ec4ce4a2bbdu64,
+            0x6c307511b2b9437au64,
+            0x47229389571aa876u64,
+            0x371ecd7b27cd8130u64,
+            0xc37459eef50bea63u64,
+            0x1a2a73ed562b0f79u64,
+            0x6142ea7d17be3111u64,
+            0x5be6128e18c26797u64,
+            0x580d7b71d8f56413u64,
+            0x43b7a3a69a8dca03u64,
+            0xb1f9163ce9ff57fu64,
+            0x759cde66bacfb3d0u64,
+        ]);
+        let exponent = BigUInt1024::new(&[
+            0x1ff49b7889463e85u64,
+            0xec1b8ca1f91e1d4cu64,
+            0x142c3fe860e7a113u64,
+            0x4b0dbb418d5288f1u64,
+            0xa0ee89aed453dd32u64,
+            0xe2acf72f9e574f7au64,
+            0x5c941cf0dc98d2c1u64,
+            0x3139d32c93cd59bfu64,
+            0x11ce5dd2b45ed1f0u64,
+            0xa9488d990bbb2599u64,
+            0xc5e7ce8a3a578a8eu64,
+            0xfc377a4c4a15544du64,
+            0xdaf61a26146d3f31u64,
+            0xddd1dfb23b982ef8u64,
+            0x614ff3d719db3ad0u64,
+            0x7412b29347294739u64,
+        ]);
+        let modulus = BigUInt1024::new(&[
+            0x1c80317fa3b1799du64,
+            0xbdd640fb06671ad1u64,
+            0x3eb13b9046685257u64,
+            0x23b8c1e9392456deu64,
+            0x1a3d1fa7bc8960a9u64,
+            0xbd9c66b3ad3c2d6du64,
+            0x8b9d2434e465e150u64,
+            0x972a846916419f82u64,
+            0x822e8f36c031199u64,
+            0x17fc695a07a0ca6eu64,
+            0x3b8faa1837f8a88bu64,
+            0x9a1de644815ef6d1u64,
+            0x8fadc1a606cb0fb3u64,
+            0xb74d0fb132e70629u64,
+            0xb38a088ca65ed389u64,
+            0xeb65a6a48b8148f6u64,
+        ]);
+        let result = BigUInt1024::new(&[
+            0x23fee8be09f15e5fu64,
+            0x1e5a024d65905543u64,
+            0xca7fa17e80f131dbu64,
+            0x1db17066596279a6u64,
+            0x7dd2da756902507cu64,
+            0xabe28a1bfe85dccau64,
+            0xec7ac28e56a4a844u64,
+            0x465b52d26c9ce338u64,
+            0xced4cf986f58f7abu64,
+            0x4d5d7a8e73268aeu64,
+            0x9c95e22cfe30535fu64,
+            0x3ab70db4629134dfu64,
+            0x3e9f1fd0202446fbu64,
+            0xd5bc035e4af3a357u64,
+            0xa5816eba24cca5a4u64,
+            0xd4b1189a706ae820u64,
+        ]);
+        assert_eq!(base.ct_modpow(&exponent, &modulus), result);
+    }
+
+    // test constant-time modpow on edge cases, agreeing with the plain version
+    #[test]
+    fn ct_modpow_test2() {
+        let modulus = BigUInt256::new(&[u64::MAX; 4]);
+        let mut chunks = [u64::MAX; 4];
+        chunks[0] -= 1;
+        let minus_one = BigUInt256::new(&chunks);
+        let two = BigUInt256::new(&[2u64]);
+        for (base, exponent) in [
+            (&two, &BigUInt256::new(&[256 * 5 + 7])),
+            (&minus_one, &modulus),
+            (&minus_one, &BigUInt256::default()),
+            (&BigUInt256::default(), &two),
+        ] {
+            assert_eq!(
+                base.ct_modpow(exponent, &modulus),
+                base.modpow(exponent, &modulus)
+            );
+        }
+    }
+
+    // test that constant-time modpow takes the same steps whatever the base and exponent
+    #[test]
+    fn ct_modpow_path_test() {
+        let modulus = BigUInt256::new(&[0x1c80317fa3b1799du64, 0xbdd640fb06671ad1u64, 0x1u64]);
+        let zero = BigUInt256::default();
+        let one = BigUInt256::new(&[1u64]);
+        let max = BigUInt256::new(&[u64::MAX; 4]);
+        let mut paths = vec![];
+        for (base, exponent) in [(&zero, &zero), (&one, &max), (&max, &one), (&max, &max)] {
+            trace::take();
+            let _ = base.ct_modpow(exponent, &modulus);
+            paths.push(trace::take());
+        }
+        assert!(paths[0].0 > 0);
+        assert!(paths.iter().all(|path| *path == paths[0]));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::radix::ParseBigUIntError;
+use crate::BigUInt;
+use num_traits::ops::overflowing::{OverflowingAdd, OverflowingMul, OverflowingSub};
+use num_traits::ops::saturating::{SaturatingAdd, SaturatingMul, SaturatingSub};
+use num_traits::ops::wrapping::{WrappingAdd, WrappingMul, WrappingSub};
+use num_traits::{
+    Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, ConstOne, ConstZero,
+    FromPrimitive, Num, One, Pow, ToPrimitive, Unsigned, Zero,
+};
+
+impl<const LIMBS: usize> Zero for BigUInt<LIMBS> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        BigUInt::is_zero(self)
+    }
+}
+
+impl<const LIMBS: usize> ConstZero for BigUInt<LIMBS> {
+    const ZERO: Self = Self::ZERO;
+}
+
+impl<const LIMBS: usize> One for BigUInt<LIMBS> {
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn is_one(&self) -> bool {
+        BigUInt::is_one(self)
+    }
+}
+
+impl<const LIMBS: usize> ConstOne for BigUInt<LIMBS> {
+    const ONE: Self = Self::ONE;
+}
+
+impl<const LIMBS: usize> Num for BigUInt<LIMBS> {
+    type FromStrRadixErr = ParseBigUIntError;
+
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        BigUInt::from_str_radix(src, radix)
+    }
+}
+
+impl<const LIMBS: usize> Unsigned for BigUInt<LIMBS> {}
+
+impl<const LIMBS: usize> Bounded for BigUInt<LIMBS> {
+    fn min_value() -> Self {
+        Self::ZERO
+    }
+
+    fn max_value() -> Self {
+        Self::MAX
+    }
+}
+
+impl<const LIMBS: usize> CheckedAdd for BigUInt<LIMBS> {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        BigUInt::checked_add(self, other)
+    }
+}
+
+impl<const LIMBS: usize> CheckedSub for BigUInt<LIMBS> {
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        BigUInt::checked_sub(self, other)
+    }
+}
+
+impl<const LIMBS: usize> CheckedMul for BigUInt<LIMBS> {
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        BigUInt::checked_mul(self, other)
+    }
+}
+
+impl<const LIMBS: usize> CheckedDiv for BigUInt<LIMBS> {
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        BigUInt::checked_div(self, other)
+    }
+}
+
+impl<const LIMBS: usize> CheckedRem for BigUInt<LIMBS> {
+    fn checked_rem(&self, other: &Self) -> Option<Self> {
+        BigUInt::checked_rem(self, other)
+    }
+}
+
+impl<const LIMBS: usize> WrappingAdd for BigUInt<LIMBS> {
+    fn wrapping_add(&self, other: &Self) -> Self {
+        BigUInt::wrapping_add(self, other)
+    }
+}
+
+impl<const LIMBS: usize> WrappingSub for BigUInt<LIMBS> {
+    fn wrapping_sub(&self, other: &Self) -> Self {
+        BigUInt::wrapping_sub(self, other)
+    }
+}
+
+impl<const LIMBS: usize> WrappingMul for BigUInt<LIMBS> {
+    fn wrapping_mul(&self, other: &Self) -> Self {
+        BigUInt::wrapping_mul(self, other)
+    }
+}
+
+impl<const LIMBS: usize> SaturatingAdd for BigUInt<LIMBS> {
+    fn saturating_add(&self, other: &Self) -> Self {
+        BigUInt::saturating_add(self, other)
+    }
+}
+
+impl<const LIMBS: usize> SaturatingSub for BigUInt<LIMBS> {
+    fn saturating_sub(&self, other: &Self) -> Self {
+        BigUInt::saturating_sub(self, other)
+    }
+}
+
+impl<const LIMBS: usize> SaturatingMul for BigUInt<LIMBS> {
+    fn saturating_mul(&self, other: &Self) -> Self {
+        BigUInt::saturating_mul(self, other)
+    }
+}
+
+impl<const LIMBS: usize> OverflowingAdd for BigUInt<LIMBS> {
+    fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        BigUInt::overflowing_add(self, other)
+    }
+}
+
+impl<const LIMBS: usize> OverflowingSub for BigUInt<LIMBS> {
+    fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        BigUInt::overflowing_sub(self, other)
+    }
+}
+
+impl<const LIMBS: usize> OverflowingMul for BigUInt<LIMBS> {
+    fn overflowing_mul(&self, other: &Self) -> (Self, bool) {
+        BigUInt::overflowing_mul(self, other)
+    }
+}
+
+impl<const LIMBS: usize> Pow<u32> for BigUInt<LIMBS> {
+    type Output = Self;
+
+    fn pow(self, exponent: u32) -> Self {
+        BigUInt::pow(&self, exponent)
+    }
+}
+
+impl<const LIMBS: usize> Pow<u32> for &BigUInt<LIMBS> {
+    type Output = BigUInt<LIMBS>;
+
+    fn pow(self, exponent: u32) -> BigUInt<LIMBS> {
+        BigUInt::pow(self, exponent)
+    }
+}
+
+impl<const LIMBS: usize> ToPrimitive for BigUInt<LIMBS> {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_u64()?.try_into().ok()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self.clone()).ok()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.to_u128()?.try_into().ok()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        u128::try_from(self.clone()).ok()
+    }
+}
+
+impl<const LIMBS: usize> FromPrimitive for BigUInt<LIMBS> {
+    fn from_i64(n: i64) -> Option<Self> {
+        u64::try_from(n).ok().map(Self::from)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Self::from(n))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        u128::try_from(n).ok().map(Self::from)
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(Self::from(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigUInt256, BigUInt4096};
+    use num_traits::{
+        Bounded, CheckedAdd, CheckedDiv, CheckedSub, FromPrimitive, Num, One, Pow, ToPrimitive,
+        Unsigned, WrappingSub, Zero,
+    };
+
+    // evaluates x^2 + x + 1 using only the num-traits interface
+    fn generic_poly<T: Num + Clone>(x: T) -> T {
+        x.clone() * x.clone() + x + T::one()
+    }
+
+    // evaluates the sum of the slice, or None on overflow
+    fn generic_checked_sum<T: Zero + CheckedAdd + Unsigned>(values: &[T]) -> Option<T> {
+        values
+            .iter()
+            .try_fold(T::zero(), |sum, value| sum.checked_add(value))
+    }
+
+    // test the type works in code generic over num-traits
+    #[test]
+    fn generic_test() {
+        assert_eq!(
+            generic_poly(BigUInt4096::from(10u64)),
+            BigUInt4096::from(111u64)
+        );
+        assert_eq!(generic_poly(10u64), 111);
+        let values = [BigUInt256::MAX, BigUInt256::one()];
+        assert_eq!(generic_checked_sum(&values[..1]), Some(BigUInt256::MAX));
+        assert_eq!(generic_checked_sum(&values), None);
+    }
+
+    // test the individual trait methods
+    #[test]
+    fn traits_test() {
+        assert!(Zero::is_zero(&BigUInt4096::zero()));
+        assert!(One::is_one(&BigUInt4096::one()));
+        assert_eq!(BigUInt256::max_value(), BigUInt256::MAX);
+        assert_eq!(BigUInt256::min_value(), BigUInt256::ZERO);
+        assert_eq!(
+            <BigUInt256 as Num>::from_str_radix("ff", 16),
+            Ok(BigUInt256::from(255u64))
+        );
+        assert_eq!(
+            CheckedSub::checked_sub(&BigUInt256::ZERO, &BigUInt256::ONE),
+            None
+        );
+        assert_eq!(
+            WrappingSub::wrapping_sub(&BigUInt256::ZERO, &BigUInt256::ONE),
+            BigUInt256::MAX
+        );
+        assert_eq!(
+            CheckedDiv::checked_div(&BigUInt256::ONE, &BigUInt256::ZERO),
+            None
+        );
+        assert_eq!(
+            Pow::pow(BigUInt256::from(2u64), 10u32),
+            BigUInt256::from(1024u64)
+        );
+        assert_eq!(BigUInt256::from(300u64).to_u8(), None);
+        assert_eq!(BigUInt256::from(300u64).to_i64(), Some(300));
+        assert_eq!(BigUInt256::from(u64::MAX).to_i64(), None);
+        assert_eq!(BigUInt256::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(BigUInt256::from_i64(-1), None);
+        assert_eq!(BigUInt256::from_i32(5), Some(BigUInt256::from(5u64)));
+        assert_eq!(BigUInt256::from_f64(2.0), Some(BigUInt256::from(2u64)));
+        // just above u64::MAX, which the num-traits default would refuse
+        let above = u64::MAX as u128 + 1;
+        assert_eq!(
+            BigUInt4096::from_u128(above),
+            Some(BigUInt4096::new(&[0, 1]))
+        );
+        assert_eq!(
+            BigUInt4096::from_i128(above as i128),
+            Some(BigUInt4096::new(&[0, 1]))
+        );
+        assert_eq!(
+            BigUInt256::from_u128(u128::MAX),
+            Some(BigUInt256::from(u128::MAX))
+        );
+        assert_eq!(BigUInt256::from_i128(-1), None);
+    }
+}
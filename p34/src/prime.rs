@@ -0,0 +1,200 @@
+use crate::montgomery::MontgomeryContext;
+use crate::random::{OsRng, RngCore};
+use crate::BigUInt;
+
+/// odd primes below 1000, used for trial division ahead of Miller-Rabin
+const SMALL_PRIMES: [u64; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311, 313, 317, 331, 337, 347, 349, 353, 359, 367, 373, 379, 383, 389, 397, 401, 409, 419, 421,
+    431, 433, 439, 443, 449, 457, 461, 463, 467, 479, 487, 491, 499, 503, 509, 521, 523, 541, 547,
+    557, 563, 569, 571, 577, 587, 593, 599, 601, 607, 613, 617, 619, 631, 641, 643, 647, 653, 659,
+    661, 673, 677, 683, 691, 701, 709, 719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797,
+    809, 811, 821, 823, 827, 829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907, 911, 919, 929,
+    937, 941, 947, 953, 967, 971, 977, 983, 991, 997,
+];
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// `Some(true)` if `self` is one of the small primes, `Some(false)` if a small prime
+    /// divides it, `None` if trial division cannot tell
+    fn trial_division(&self) -> Option<bool> {
+        if self.bits() <= 1 {
+            return Some(false);
+        }
+        if !self.bit(0) {
+            return Some(self.chunks[0] == 2 && self.bits() == 2);
+        }
+        if self.bits() <= 64 && SMALL_PRIMES.contains(&self.chunks[0]) {
+            return Some(true);
+        }
+        // divide by products of several primes at once, then reduce the remainder per prime
+        let mut start = 0;
+        while start < SMALL_PRIMES.len() {
+            let mut product = 1u64;
+            let mut end = start;
+            while let Some(next) = SMALL_PRIMES.get(end).and_then(|&p| product.checked_mul(p)) {
+                product = next;
+                end += 1;
+            }
+            let (_, rem) = self.div_rem_chunk(product);
+            if SMALL_PRIMES[start..end]
+                .iter()
+                .any(|&p| rem.is_multiple_of(p))
+            {
+                return Some(false);
+            }
+            start = end;
+        }
+        // no prime up to 997 divides it, so it is prime if below 997^2
+        if self.bits() <= 64 && self.chunks[0] < 997 * 997 {
+            return Some(true);
+        }
+        None
+    }
+
+    /// Miller-Rabin with `rounds` witnesses drawn from `rng`, for odd `self` above the small primes
+    fn miller_rabin(&self, rounds: u32, rng: &mut (impl RngCore + ?Sized)) -> bool {
+        let one = Self::new(&[1u64]);
+        let minus_one = self - &one;
+        // self - 1 = d * 2^s with d odd
+        let s = minus_one.trailing_zeros();
+        let d = &minus_one >> s;
+        let ctx = MontgomeryContext::new(self).unwrap();
+        let one_m = ctx.to_montgomery(&one);
+        let minus_one_m = ctx.to_montgomery(&minus_one);
+        // witnesses are uniform in [2, self - 2]
+        let bound = self - Self::new(&[3u64]);
+        'witness: for _ in 0..rounds {
+            let witness = Self::random_below(&bound, rng) + Self::new(&[2u64]);
+            let mut x = ctx.to_montgomery(&ctx.modpow(&witness, &d));
+            if x == one_m || x == minus_one_m {
+                continue;
+            }
+            for _ in 1..s {
+                x = ctx.mul(&x, &x);
+                if x == minus_one_m {
+                    continue 'witness;
+                }
+                if x == one_m {
+                    return false;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// probabilistic primality test: trial division by small primes, then `rounds` rounds of
+    /// Miller-Rabin with witnesses from the operating system's generator; a composite passes
+    /// with probability at most `4^(-rounds)`
+    pub fn is_probable_prime(&self, rounds: u32) -> bool {
+        self.is_probable_prime_with_rng(rounds, &mut OsRng)
+    }
+
+    /// `is_probable_prime` with Miller-Rabin witnesses drawn from `rng`
+    pub fn is_probable_prime_with_rng(
+        &self,
+        rounds: u32,
+        rng: &mut (impl RngCore + ?Sized),
+    ) -> bool {
+        match self.trial_division() {
+            Some(answer) => answer,
+            None => self.miller_rabin(rounds, rng),
+        }
+    }
+
+    /// random prime of exactly `bits` bits, with its two top bits set so that the product of
+    /// two such primes has exactly `2 * bits` bits; candidates are tested with 64 rounds of
+    /// Miller-Rabin, and `bits` must be between 2 and the width
+    pub fn random_prime(bits: u32, rng: &mut (impl RngCore + ?Sized)) -> Self {
+        assert!(
+            (2..=64 * LIMBS as u32).contains(&bits),
+            "Bit count out of range"
+        );
+        loop {
+            let mut candidate = Self::random_bits(bits, rng);
+            candidate.set_bit(bits - 1, true);
+            candidate.set_bit(bits - 2, true);
+            candidate.set_bit(0, true);
+            if candidate.is_probable_prime_with_rng(64, rng) {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::TestRng;
+    use crate::{BigUInt1024, BigUInt256, BigUInt512};
+
+    // test small values against a sieve
+    #[test]
+    fn is_probable_prime_test1() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let mut sieve = vec![true; 20000];
+        sieve[0] = false;
+        sieve[1] = false;
+        for i in 2..sieve.len() {
+            if sieve[i] {
+                for j in (2 * i..sieve.len()).step_by(i) {
+                    sieve[j] = false;
+                }
+            }
+        }
+        for (n, &prime) in sieve.iter().enumerate() {
+            let value = BigUInt256::new(&[n as u64]);
+            assert_eq!(
+                value.is_probable_prime_with_rng(20, &mut rng),
+                prime,
+                "{}",
+                n
+            );
+        }
+    }
+
+    // test known large primes and composites, including strong pseudoprimes to small bases
+    #[test]
+    fn is_probable_prime_test2() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let one = BigUInt1024::new(&[1u64]);
+        // Mersenne primes 2^521 - 1 and 2^607 - 1, and the composite 2^523 - 1
+        for (exp, prime) in [(521, true), (607, true), (523, false)] {
+            let value = (one.clone() << exp) - &one;
+            assert_eq!(value.is_probable_prime_with_rng(20, &mut rng), prime);
+        }
+        // Carmichael numbers and strong pseudoprimes to bases 2, 3, 5 and 7
+        for n in [
+            561u64,
+            1105,
+            41041,
+            3215031751,
+            2152302898747,
+            3474749660383,
+        ] {
+            assert!(!BigUInt1024::new(&[n]).is_probable_prime_with_rng(20, &mut rng));
+        }
+        // 2^64 - 59 is the largest prime below 2^64, and the product of two Mersenne primes
+        assert!(BigUInt1024::new(&[u64::MAX - 58]).is_probable_prime(20));
+        let m61 = (one.clone() << 61) - &one;
+        let m89 = (one.clone() << 89) - &one;
+        assert!(m61.is_probable_prime(20) && m89.is_probable_prime(20));
+        assert!(!(m61 * m89).is_probable_prime(20));
+    }
+
+    // test random primes have the requested size and are reproducible from the seed
+    #[test]
+    fn random_prime_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        for bits in [2, 3, 10, 64, 65, 256] {
+            let prime = BigUInt512::random_prime(bits, &mut rng);
+            assert_eq!(prime.bits(), bits);
+            assert!(prime.is_probable_prime(20));
+        }
+        let one = BigUInt1024::random_prime(512, &mut TestRng(42));
+        let other = BigUInt1024::random_prime(512, &mut TestRng(42));
+        assert_eq!(one, other);
+        assert_eq!((&one * &other).bits(), 1024);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::BigUInt;
+use std::fmt;
+use std::str::FromStr;
+
+/// error returned when parsing a `BigUInt` from a string fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseBigUIntError {
+    /// the string has no digits
+    Empty,
+    /// the string contains a character that is not a digit in the given radix
+    InvalidDigit,
+    /// the value does not fit in the integer width
+    Overflow,
+}
+
+impl fmt::Display for ParseBigUIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "cannot parse integer from empty string"),
+            Self::InvalidDigit => write!(f, "invalid digit found in string"),
+            Self::Overflow => write!(f, "number too large to fit in target type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigUIntError {}
+
+/// largest power of `radix` that fits in a u64, and its exponent
+fn chunk_radix(radix: u32) -> (u64, usize) {
+    let mut power = radix as u64;
+    let mut digits = 1;
+    while let Some(next) = power.checked_mul(radix as u64) {
+        power = next;
+        digits += 1;
+    }
+    (power, digits)
+}
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// multiplies by `mul` and adds `add` in place, returning the chunk that overflows the top
+    fn mul_add_chunk(&mut self, mul: u64, add: u64) -> u64 {
+        let mut carry = add;
+        for chunk in self.chunks[..self.len].iter_mut() {
+            let acc = *chunk as u128 * mul as u128 + carry as u128;
+            *chunk = acc as u64;
+            carry = (acc >> 64) as u64;
+        }
+        // the chunks above are zero, so the carry lands in the next one up
+        if self.len < LIMBS {
+            self.chunks[self.len] = carry;
+            self.trim_len(self.len + 1);
+            carry = 0;
+        }
+        carry
+    }
+
+    /// parses a string of digits in the given radix, with an optional leading `+`;
+    /// panics if `radix` is not in the range 2 to 36
+    pub fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseBigUIntError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "Radix must be in the range 2 to 36"
+        );
+        let digits = src.strip_prefix('+').unwrap_or(src).as_bytes();
+        if digits.is_empty() {
+            return Err(ParseBigUIntError::Empty);
+        }
+        // take the digits in batches that fit a single chunk
+        let (_, batch_len) = chunk_radix(radix);
+        let mut value = Self::default();
+        let mut overflow = false;
+        for batch in digits.chunks(batch_len) {
+            let mut batch_value: u64 = 0;
+            for &digit in batch {
+                let digit = (digit as char)
+                    .to_digit(radix)
+                    .ok_or(ParseBigUIntError::InvalidDigit)?;
+                batch_value = batch_value * radix as u64 + digit as u64;
+            }
+            let batch_radix = (radix as u64).pow(batch.len() as u32);
+            overflow = overflow || value.mul_add_chunk(batch_radix, batch_value) != 0;
+        }
+        // bad digits take precedence, so overflow is only reported once every digit is checked
+        if overflow {
+            return Err(ParseBigUIntError::Overflow);
+        }
+        Ok(value)
+    }
+
+    /// formats the value as lowercase digits in the given radix;
+    /// panics if `radix` is not in the range 2 to 36
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "Radix must be in the range 2 to 36"
+        );
+        let (batch_radix, batch_len) = chunk_radix(radix);
+        // peel off batches of digits from the bottom, least significant first
+        let mut digits: Vec<u8> = vec![];
+        let mut rest = self.clone();
+        loop {
+            let (quot, mut batch_value) = rest.div_rem_chunk(batch_radix);
+            for _ in 0..batch_len {
+                let digit = (batch_value % radix as u64) as u32;
+                digits.push(std::char::from_digit(digit, radix).unwrap() as u8);
+                batch_value /= radix as u64;
+            }
+            rest = quot;
+            if rest.is_zero() {
+                break;
+            }
+        }
+        // drop leading zeros, but keep a single zero for the value zero
+        while digits.len() > 1 && digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+        digits.reverse();
+        String::from_utf8(digits).unwrap()
+    }
+}
+
+impl<const LIMBS: usize> FromStr for BigUInt<LIMBS> {
+    type Err = ParseBigUIntError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Self::from_str_radix(src, 10)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Display for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl<const LIMBS: usize> fmt::LowerHex for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl<const LIMBS: usize> fmt::UpperHex for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_uppercase())
+    }
+}
+
+impl<const LIMBS: usize> fmt::Binary for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &self.to_str_radix(2))
+    }
+}
+
+impl<const LIMBS: usize> fmt::Octal for BigUInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0o", &self.to_str_radix(8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix::ParseBigUIntError;
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test decimal parsing and formatting
+    #[test]
+    fn decimal_test() {
+        let num = BigUInt4096::new(&[0u64, 0u64, 1u64]);
+        let text = "340282366920938463463374607431768211456";
+        assert_eq!(text.parse::<BigUInt4096>(), Ok(num.clone()));
+        assert_eq!(num.to_string(), text);
+        assert_eq!(BigUInt4096::default().to_string(), "0");
+        assert_eq!("+0000".parse::<BigUInt4096>(), Ok(BigUInt4096::default()));
+    }
+
+    // test parsing and formatting in other radixes
+    #[test]
+    fn radix_test() {
+        let num = BigUInt4096::new(&[1295u64]);
+        assert_eq!(BigUInt4096::from_str_radix("zZ", 36), Ok(num.clone()));
+        assert_eq!(num.to_str_radix(36), "zz");
+        assert_eq!(num.to_str_radix(2), "10100001111");
+        let num = BigUInt4096::new(&[0x0123456789abcdefu64, 0xfedcba9876543210u64]);
+        let text = "fedcba98765432100123456789abcdef";
+        assert_eq!(BigUInt4096::from_str_radix(text, 16), Ok(num.clone()));
+        assert_eq!(num.to_str_radix(16), text);
+        for radix in 2..=36 {
+            let text = num.to_str_radix(radix);
+            assert_eq!(BigUInt4096::from_str_radix(&text, radix), Ok(num.clone()));
+        }
+    }
+
+    // test round trips at full width
+    #[test]
+    fn full_width_test() {
+        let num = BigUInt256::new(&[u64::MAX; 4]);
+        let text = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(num.to_string(), text);
+        assert_eq!(text.parse::<BigUInt256>(), Ok(num));
+        let num = BigUInt4096::new(&[u64::MAX; 64]);
+        assert_eq!(format!("{:x}", num), "f".repeat(1024));
+        assert_eq!(num.to_string().parse::<BigUInt4096>(), Ok(num));
+    }
+
+    // test parse errors
+    #[test]
+    fn parse_error_test() {
+        assert_eq!("".parse::<BigUInt4096>(), Err(ParseBigUIntError::Empty));
+        assert_eq!("+".parse::<BigUInt4096>(), Err(ParseBigUIntError::Empty));
+        assert_eq!(
+            "12a".parse::<BigUInt4096>(),
+            Err(ParseBigUIntError::InvalidDigit)
+        );
+        assert_eq!(
+            "-1".parse::<BigUInt4096>(),
+            Err(ParseBigUIntError::InvalidDigit)
+        );
+        assert_eq!(
+            BigUInt4096::from_str_radix("102", 2),
+            Err(ParseBigUIntError::InvalidDigit)
+        );
+        let too_big =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        assert_eq!(
+            too_big.parse::<BigUInt256>(),
+            Err(ParseBigUIntError::Overflow)
+        );
+        assert_eq!(
+            BigUInt256::from_str_radix(&"f".repeat(65), 16),
+            Err(ParseBigUIntError::Overflow)
+        );
+    }
+
+    // test that a radix above 36 panics
+    #[test]
+    #[should_panic(expected = "Radix must be in the range 2 to 36")]
+    fn invalid_radix_test() {
+        let _ = BigUInt4096::from_str_radix("1", 37);
+    }
+
+    // test the formatting traits and their flags
+    #[test]
+    fn format_test() {
+        let num = BigUInt4096::new(&[0xabcu64]);
+        assert_eq!(format!("{}", num), "2748");
+        assert_eq!(format!("{:>6}", num), "  2748");
+        assert_eq!(format!("{:x}", num), "abc");
+        assert_eq!(format!("{:#X}", num), "0xABC");
+        assert_eq!(format!("{:#010x}", num), "0x00000abc");
+        assert_eq!(format!("{:b}", num), "101010111100");
+        assert_eq!(format!("{:#o}", num), "0o5274");
+    }
+}
@@ -0,0 +1,109 @@
+//! Uniform random generation of `BigUInt` values from a pluggable source of randomness.
+//!
+//! Any `RngCore` can be passed in; `OsRng` draws from the operating system via `getrandom`
+//! and is what the crate uses when no generator is given.
+
+use crate::BigUInt;
+pub use rand_core::{CryptoRng, OsRng, RngCore};
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// uniformly random value below `2^bits`, panicking if `bits` exceeds the width
+    pub fn random_bits(bits: u32, rng: &mut (impl RngCore + ?Sized)) -> Self {
+        assert!(bits as usize <= 64 * LIMBS, "Bit count out of range");
+        let mut value = Self::default();
+        let full = (bits / 64) as usize;
+        for chunk in value.chunks[..full].iter_mut() {
+            *chunk = rng.next_u64();
+        }
+        if !bits.is_multiple_of(64) {
+            value.chunks[full] = rng.next_u64() >> (64 - bits % 64);
+        }
+        value.trim_len(full + 1);
+        value
+    }
+
+    /// uniformly random value in `[0, bound)` by rejection sampling, panicking if `bound` is zero
+    pub fn random_below(bound: &Self, rng: &mut (impl RngCore + ?Sized)) -> Self {
+        assert!(!bound.is_zero(), "Zero bound");
+        loop {
+            let value = Self::random_bits(bound.bits(), rng);
+            if &value < bound {
+                return value;
+            }
+        }
+    }
+}
+
+/// deterministic xorshift generator for tests
+#[cfg(test)]
+pub(crate) struct TestRng(pub u64);
+
+#[cfg(test)]
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl CryptoRng for TestRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::{OsRng, TestRng};
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test random_bits stays within the requested bit count
+    #[test]
+    fn random_bits_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        for bits in [0, 1, 63, 64, 65, 200, 256] {
+            for _ in 0..20 {
+                assert!(BigUInt256::random_bits(bits, &mut rng).bits() <= bits);
+            }
+        }
+        // full width values reach the top bit now and then
+        assert!((0..20).any(|_| BigUInt256::random_bits(256, &mut rng).bits() == 256));
+    }
+
+    // test random_below stays below the bound and hits every value of a small range
+    #[test]
+    fn random_below_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let bound = BigUInt256::new(&[10u64]);
+        let mut counts = [0u32; 10];
+        for _ in 0..1000 {
+            let value = BigUInt256::random_below(&bound, &mut rng);
+            assert!(value < bound);
+            counts[value.chunks[0] as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| (60..140).contains(&count)));
+        let bound = BigUInt4096::new(&[0, 0, 0, 1]);
+        for _ in 0..20 {
+            assert!(BigUInt4096::random_below(&bound, &mut OsRng) < bound);
+        }
+    }
+
+    // test random_below panics on a zero bound
+    #[test]
+    #[should_panic(expected = "Zero bound")]
+    fn random_below_zero_test() {
+        BigUInt256::random_below(&BigUInt256::default(), &mut TestRng(1));
+    }
+}
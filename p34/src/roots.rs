@@ -0,0 +1,286 @@
+use crate::BigUInt;
+
+impl<const LIMBS: usize> BigUInt<LIMBS> {
+    /// returns `self^exponent` modulo 2^(64 * LIMBS) and whether the true power overflowed,
+    /// by left-to-right square-and-multiply
+    pub fn overflowing_pow(&self, exponent: u32) -> (Self, bool) {
+        let mut result = Self::new(&[1u64]);
+        let mut overflow = false;
+        for i in (0..u32::BITS - exponent.leading_zeros()).rev() {
+            let (square, square_overflow) = result.overflowing_mul(&result);
+            result = square;
+            overflow |= square_overflow;
+            if exponent >> i & 1 == 1 {
+                let (prod, prod_overflow) = result.overflowing_mul(self);
+                result = prod;
+                overflow |= prod_overflow;
+            }
+        }
+        // for a non-zero base every intermediate value divides the final power, so an overflow
+        // anywhere means the power overflows
+        (result, overflow)
+    }
+
+    pub fn checked_pow(&self, exponent: u32) -> Option<Self> {
+        match self.overflowing_pow(exponent) {
+            (power, false) => Some(power),
+            _ => None,
+        }
+    }
+
+    pub fn wrapping_pow(&self, exponent: u32) -> Self {
+        self.overflowing_pow(exponent).0
+    }
+
+    /// returns `self^exponent`, panicking on overflow in debug builds like `Mul`
+    pub fn pow(&self, exponent: u32) -> Self {
+        let (power, overflow) = self.overflowing_pow(exponent);
+        debug_assert!(!overflow, "Overflow while exponentiating");
+        power
+    }
+
+    /// floor of the square root, by Newton iteration from an overestimate
+    pub fn isqrt(&self) -> Self {
+        self.nth_root(2)
+    }
+
+    /// floor of the `n`th root, by Newton iteration `x <- ((n - 1) x + self / x^(n - 1)) / n`
+    /// from an overestimate; panics if `n` is zero
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n != 0, "Zeroth root");
+        if n == 1 || self.bits() <= 1 {
+            return self.clone();
+        }
+        if n >= self.bits() {
+            return Self::new(&[1u64]);
+        }
+        let n_big = Self::new(&[n as u64]);
+        let n_minus_one = Self::new(&[n as u64 - 1]);
+        // 2^ceil(bits / n) is above the root, and the iteration decreases until it reaches it
+        let mut root = Self::new(&[1u64]) << self.bits().div_ceil(n);
+        loop {
+            let quot = match root.checked_pow(n - 1) {
+                Some(power) => self / power,
+                None => Self::default(),
+            };
+            let next = (&root * &n_minus_one + quot) / &n_big;
+            if next >= root {
+                return root;
+            }
+            root = next;
+        }
+    }
+
+    /// whether `self` is the square of an integer
+    pub fn is_perfect_square(&self) -> bool {
+        // squares are 0, 1, 4 or 9 modulo 16
+        if ![0, 1, 4, 9].contains(&(self.chunks[0] & 15)) {
+            return false;
+        }
+        let root = self.isqrt();
+        &root * &root == *self
+    }
+
+    /// whether `self` is `m^k` for some integers `m` and `k >= 2`; 0 and 1 count
+    pub fn is_perfect_power(&self) -> bool {
+        if self.bits() <= 1 {
+            return true;
+        }
+        // a perfect power is a perfect p-th power for some prime p, and p < bits
+        let bits = self.bits();
+        let mut composite = vec![false; bits as usize];
+        (2..bits).any(|k| {
+            if composite[k as usize] {
+                return false;
+            }
+            for multiple in (2 * k..bits).step_by(k as usize) {
+                composite[multiple as usize] = true;
+            }
+            self.nth_root(k).checked_pow(k).as_ref() == Some(self)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigUInt256, BigUInt4096};
+
+    // test pow and its overflow variants on small values against u128
+    #[test]
+    fn pow_test1() {
+        for base in [0u64, 1, 2, 3, 7, 255, 1 << 20, u64::MAX] {
+            for exponent in [0u32, 1, 2, 3, 5, 8, 13] {
+                let value = BigUInt256::new(&[base]);
+                // u128 holds every power that does not overflow it, which is all we compare
+                if let Some(power) = (base as u128).checked_pow(exponent) {
+                    let power = BigUInt256::new(&[power as u64, (power >> 64) as u64]);
+                    assert_eq!(value.pow(exponent), power);
+                    assert_eq!(value.checked_pow(exponent), Some(power));
+                }
+            }
+        }
+        let two = BigUInt256::new(&[2u64]);
+        assert_eq!(
+            two.overflowing_pow(255),
+            (BigUInt256::new(&[0, 0, 0, 1 << 63]), false)
+        );
+        assert_eq!(two.overflowing_pow(256), (BigUInt256::default(), true));
+        assert_eq!(two.checked_pow(256), None);
+        assert_eq!(two.wrapping_pow(257), BigUInt256::default());
+        assert_eq!(
+            BigUInt256::new(&[3u64]).wrapping_pow(300),
+            BigUInt256::new(&[3u64]).overflowing_pow(300).0
+        );
+        assert_eq!(
+            BigUInt256::default().overflowing_pow(1000),
+            (BigUInt256::default(), false)
+        );
+    }
+
+    // test pow panics on overflow in debug builds
+    #[test]
+    #[should_panic(expected = "Overflow while exponentiating")]
+    fn pow_overflow_test() {
+        let _ = BigUInt256::new(&[2u64]).pow(256);
+    }
+
+    // test isqrt and nth_root on small values against exhaustive search
+    #[test]
+    fn nth_root_test1() {
+        for value in 0u64..2000 {
+            let big = BigUInt256::new(&[value]);
+            for n in 1u32..6 {
+                let root = (0u64..).take_while(|r| r.pow(n) <= value).last().unwrap();
+                assert_eq!(big.nth_root(n), BigUInt256::new(&[root]), "{} {}", value, n);
+            }
+            assert_eq!(big.isqrt(), big.nth_root(2));
+        }
+        let max = !BigUInt256::default();
+        assert_eq!(max.isqrt(), BigUInt256::new(&[u64::MAX, u64::MAX]));
+        assert_eq!(max.nth_root(256), BigUInt256::new(&[1u64]));
+        assert_eq!(max.nth_root(255), BigUInt256::new(&[2u64]));
+    }
+
+    // test roots of a large value against values computed in Python
+    #[test]
+    fn nth_root_test2() {
+        let value = BigUInt4096::from_str_radix(
+            concat!(
+                "5502bfbd2bb4c81151a20dc3590ad570eaa1a8c34ebf6be1a897b98f400b49322125fd8ae6d747b4",
+                "8db0eb42887b5f5f8e384a7bd422051f9a98f4d0bf5da6cc3157e672468629e8c95613ef1071e6da",
+                "44673e230ee06e0b5c15cfd1f515f75186e415243fa244adf517a77536be6e688e8b88c21df99531",
+                "3d2b9a3667cc1752de27660b01520627c63d6f69947feaa35ff4cb507a2c3b72f5a2224a0ce02d28",
+                "5ac9cd0ebd27b4f2c0855ac9e599e3580d3a2dc6925b1ec95f4807bc6ecace3c0490ca26561d2417",
+                "eb837ba8818dced3d0398c72689edcd6cfec9e2caebf999324405d969995d8aaff0f3b81a3e2889c",
+                "795e846bdbd5f6d2f09529af81dda9da14f5079168e06b0c4f27b35c11b5aecda386a3a0b730d88f",
+                "e1e8a4aa1f9db8dd8a3b09dd54bec7d835c33744af929a91f4873115cd425ec38f1389998869510d",
+                "b4a02517e1ff83ab26a2658f32521553e014be00caa7e9bfd00724a123cf493f0febddf88d1a6bff",
+                "ff9a39142335e9e266cea9fab969ec07f1f83a79af371d87d8a8f065a3f96f0e51436d1fcd68615c",
+                "80690847dc159e6a409c38f26b68b48ebf13c171d0b0090d625909923fb81d2706e55426eae0d2c1",
+                "1c339464473d212ba950666d8a4996efb447c0ceb48438b5c41f9dfd2cb85f3f4a24e39a5d998017",
+                "f5e2fc574dad2986ce8349606a06e9ab85a0bcc1",
+            ),
+            16,
+        )
+        .unwrap();
+        let square_root = BigUInt4096::from_str_radix(
+            concat!(
+                "9385a3ade0fc9f520abdd7ce8e6848b7c3295d91f3683908f053608682bf01ac61a28f0d2a6f00ed",
+                "d8fcf297f88334e0301f98d0037894444f41e6ee6652659b2c57261dcecad5e5ecd9c0d22f6e5deb",
+                "8d43aa65fd014d2d8f56cdc1f193ff0b72076a5137e92413a18d06763f9ef85b5ec7a84fef5c1b73",
+                "61ea78bd863caeeb799ac7832257b46b46b18148fa9b44e285d07dcf7292a69645fab34c20a09b74",
+                "59b4e054e49c45b43c6807f85246db31a84f8a8f05fb15e5da538a983bc1aafbb16e2d27669402fc",
+                "b7d7a1303a67872af60e703c267f590bbb15b32f162d16b2a0847608ba82b83e46d49fbb80c5486a",
+                "39be3fdf79f06fe09349",
+            ),
+            16,
+        )
+        .unwrap();
+        assert_eq!(value.nth_root(2), square_root);
+        let cube_root = BigUInt4096::from_str_radix(
+            concat!(
+                "1beb577e35ebc179d743fcb43452a47d4916b90929efe8cbe00a274e6ebb84049b7af8ac6f832acc",
+                "a447615bd679769f2cb630d176160cf27619c14c34eaea03420ff8baef13ca5f5d0811b716528df7",
+                "b86e8a586ed2b84cea4159dae8b6addb6bd59f1676d19e543047496777e999c14a048197c49a3009",
+                "3baabcfc39576952d8f2e1b27ac9e7d42ec8abf37eb65bebfe3cc142483a2a0db89466052428bb74",
+                "894dca55e571f5",
+            ),
+            16,
+        )
+        .unwrap();
+        assert_eq!(value.nth_root(3), cube_root);
+        let fifth_root = BigUInt4096::from_str_radix(
+            concat!(
+                "cd58a2662049b66bb15eb71bb0a90c6c6144f9be627158a56de2af4096598e3f877082c6912bc29a",
+                "5c14aa4294f056a31844482c00a11562ba9ffbff3a12ed0dae6e980400afafd33dacd1c2a1856b68",
+                "807587c9be0bed8a599a4e2b9ea871c8b6309450",
+            ),
+            16,
+        )
+        .unwrap();
+        assert_eq!(value.nth_root(5), fifth_root);
+        assert_eq!(
+            value.nth_root(17),
+            BigUInt4096::from_str_radix(
+                "93173e2a3c3ff9728e6095ccc53c938e695b6d8e5e33b090c52ef414284",
+                16
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            value.nth_root(255),
+            BigUInt4096::from_str_radix("cd14", 16).unwrap()
+        );
+    }
+
+    // test a large power against a value computed in Python
+    #[test]
+    fn pow_test2() {
+        let expected = BigUInt4096::from_str_radix(
+            concat!(
+                "54d0b44cb0968d00daa614cb76b93b4c28803e140de3ca9bc670222bfc28abd5c6f5a5a37f7387aa",
+                "0f699245ca30e1b6715f8120bd20004ccee5500b9ee77deff45ab908dc1bf6d66146443b721a44ff",
+                "e1740af815be988d610ac26a15472230e807985fc7a90c380a19183a3fd49dac88c8d127a4fb3586",
+                "819173503cfc83dfe6d8a3a6ecdfa0b4ab58e039cd1e022c8a871efe66ac77d2d09753e3f0ca1de2",
+                "35da61ade6f1db66a6f99562e4da0bab5bd784124eb8658d0e426eeb1ba26b8eb8c331c418d9e7bd",
+                "9bf1b10aa3dceea251c00c59755b9548fea0eb6858ae32218022130e5466b531479287e0d3ebff63",
+                "271ccab2203cc578747f278c7042c750c51fca6ec06e4e584ebd81a35a8f1ac1b89ac3f2e0548c3f",
+                "0c376e7726345704450a16f37dfa6141f994710bed267c82a207526a9f3b45e8ee50d5fb8900f7b8",
+                "e78733344898234d35a7e4927c89b015eae4ff27bb306a09310e3d4da3f0b6f5a8c13b0d28e678fa",
+                "8f1a89e893a126439af78a9731d46b434463f6784a8b4d77be45703a5c6068c9c6ac03d18f9b7f01",
+                "446c12b454b218200fb124a6b9b8dd7a85262dbdca9baa72a54f9ea5bfe8107a7304a234fcd9f075",
+                "ba1f7a916fb4e863f58dffbbd22645ed3c9b21901e42164a947c3c65ae056ffaac6d4122717c12f8",
+                "cee185bba0c0472e3a24a83458b3b51",
+            ),
+            16,
+        )
+        .unwrap();
+        assert_eq!(BigUInt4096::new(&[3u64]).pow(2500), expected);
+        assert_eq!(BigUInt4096::new(&[3u64]).checked_pow(2585), None);
+    }
+
+    // test perfect square and perfect power detection
+    #[test]
+    fn perfect_power_test() {
+        let squares: Vec<u64> = (0..40).map(|r| r * r).collect();
+        let powers = [
+            0u64, 1, 4, 8, 9, 16, 25, 27, 32, 36, 49, 64, 81, 100, 121, 125, 128,
+        ];
+        for value in 0u64..130 {
+            let big = BigUInt256::new(&[value]);
+            assert_eq!(
+                big.is_perfect_square(),
+                squares.contains(&value),
+                "{}",
+                value
+            );
+            assert_eq!(big.is_perfect_power(), powers.contains(&value), "{}", value);
+        }
+        let base = BigUInt4096::new(&[0x1234567890abcdefu64, 0xfedcba]);
+        let power = base.pow(37);
+        assert!(power.is_perfect_power());
+        assert!(!(&power + BigUInt4096::new(&[1u64])).is_perfect_power());
+        assert!(base.pow(40).is_perfect_square());
+        assert!(!base.pow(41).is_perfect_square());
+    }
+}
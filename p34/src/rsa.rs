@@ -0,0 +1,855 @@
+//! RSA key generation, OAEP encryption and PSS signatures over `BigUInt4096`, following
+//! RFC 8017 with SHA-256 as both the hash and the MGF1 hash. PSS salts are as long as the
+//! hash, both when signing and when verifying.
+//!
+//! Private operations use the CRT on the prime factors, held at half width, with
+//! constant-time exponentiation by the CRT exponents. The reductions and recombination
+//! modulo the primes are not constant time, so the input is first blinded by `r^e` for a
+//! fresh random `r` and those steps only see a value unrelated to it; only the primes
+//! themselves, which are fixed for a key, still affect their timing. The result is checked
+//! with the public exponent before it is unblinded and released, so a faulty CRT half
+//! cannot leak a factor.
+
+use crate::montgomery::MontgomeryContext;
+use crate::random::{CryptoRng, OsRng, RngCore};
+use crate::{BigUInt2048, BigUInt4096};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// output length of the hash, in bytes
+const HASH_LEN: usize = 32;
+
+/// public exponent used for generated keys
+const PUBLIC_EXPONENT: u64 = 65537;
+
+/// Miller-Rabin rounds used to check the prime factors of an imported key; a composite that
+/// slips through is still caught by the check on every private operation
+const PRIME_ROUNDS: u32 = 20;
+
+/// errors from RSA key construction and operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RsaError {
+    /// key components are inconsistent or out of range
+    InvalidKey,
+    /// message is too long for the modulus
+    MessageTooLong,
+    /// ciphertext is malformed or does not decrypt under this key
+    Decryption,
+    /// signature does not match the message
+    Verification,
+    /// private operation failed its consistency check, so the key or the computation is faulty
+    Fault,
+}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsaError::InvalidKey => write!(f, "invalid RSA key"),
+            RsaError::MessageTooLong => write!(f, "message too long for RSA modulus"),
+            RsaError::Decryption => write!(f, "RSA decryption error"),
+            RsaError::Verification => write!(f, "invalid RSA signature"),
+            RsaError::Fault => write!(f, "RSA private operation failed its consistency check"),
+        }
+    }
+}
+
+impl std::error::Error for RsaError {}
+
+/// RSA public key `(n, e)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    n: BigUInt4096,
+    e: BigUInt4096,
+}
+
+/// RSA private key, with the CRT exponents and coefficient precomputed
+#[derive(Clone)]
+pub struct RsaPrivateKey {
+    public: RsaPublicKey,
+    d: BigUInt4096,
+    p: BigUInt2048,
+    q: BigUInt2048,
+    dp: BigUInt2048,
+    dq: BigUInt2048,
+    qinv: BigUInt2048,
+}
+
+// shows only the public key, so that secrets do not end up in logs
+impl fmt::Debug for RsaPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaPrivateKey")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// zero-extends a half-width value
+fn widen(value: &BigUInt2048) -> BigUInt4096 {
+    BigUInt4096::from_halves(value, &BigUInt2048::default())
+}
+
+/// lower half of a value known to fit in half the width
+fn narrow(value: &BigUInt4096) -> BigUInt2048 {
+    value.split().0
+}
+
+/// MGF1 with SHA-256: xors the mask generated from `seed` into `out`
+fn mgf1_xor(seed: &[u8], out: &mut [u8]) {
+    for (counter, block) in out.chunks_mut(HASH_LEN).enumerate() {
+        let mask = Sha256::new()
+            .chain_update(seed)
+            .chain_update((counter as u32).to_be_bytes())
+            .finalize();
+        for (byte, mask_byte) in block.iter_mut().zip(mask) {
+            *byte ^= mask_byte;
+        }
+    }
+}
+
+impl RsaPublicKey {
+    /// checks that `n` is odd and at least 1024 bits, and that `e` is odd with `3 <= e < n`
+    pub fn new(n: BigUInt4096, e: BigUInt4096) -> Result<Self, RsaError> {
+        let three = BigUInt4096::new(&[3u64]);
+        if !n.bit(0) || n.bits() < 1024 || !e.bit(0) || e < three || e >= n {
+            return Err(RsaError::InvalidKey);
+        }
+        Ok(RsaPublicKey { n, e })
+    }
+
+    /// modulus
+    pub fn n(&self) -> &BigUInt4096 {
+        &self.n
+    }
+
+    /// public exponent
+    pub fn e(&self) -> &BigUInt4096 {
+        &self.e
+    }
+
+    /// length of the modulus in bytes, which is also the ciphertext and signature length
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    /// big-endian encoding of `value` in exactly `len` bytes, for `value < 2^(8 * len)`
+    fn to_bytes(value: &BigUInt4096, len: usize) -> Vec<u8> {
+        let bytes = value.to_be_bytes();
+        bytes[bytes.len() - len..].to_vec()
+    }
+
+    /// parses a ciphertext or signature, which must be `size()` bytes and below the modulus
+    fn parse(&self, bytes: &[u8]) -> Option<BigUInt4096> {
+        if bytes.len() != self.size() {
+            return None;
+        }
+        BigUInt4096::from_be_bytes(bytes)
+            .ok()
+            .filter(|value| value < &self.n)
+    }
+
+    /// raw public operation `value^e mod n`
+    fn public_op(&self, value: &BigUInt4096) -> BigUInt4096 {
+        value.modpow(&self.e, &self.n)
+    }
+
+    /// RSAES-OAEP encryption of `message` with the given label
+    pub fn encrypt(
+        &self,
+        message: &[u8],
+        label: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<Vec<u8>, RsaError> {
+        let mut seed = [0u8; HASH_LEN];
+        rng.fill_bytes(&mut seed);
+        self.encrypt_with_seed(message, label, &seed)
+    }
+
+    /// RSAES-OAEP encryption with an explicit seed
+    fn encrypt_with_seed(
+        &self,
+        message: &[u8],
+        label: &[u8],
+        seed: &[u8; HASH_LEN],
+    ) -> Result<Vec<u8>, RsaError> {
+        let k = self.size();
+        if message.len() + 2 * HASH_LEN + 2 > k {
+            return Err(RsaError::MessageTooLong);
+        }
+        // EM = 0x00 || maskedSeed || maskedDB, with DB = lHash || PS || 0x01 || M
+        let mut em = vec![0u8; k];
+        let (masked_seed, db) = em[1..].split_at_mut(HASH_LEN);
+        masked_seed.copy_from_slice(seed);
+        let seed = masked_seed;
+        db[..HASH_LEN].copy_from_slice(&Sha256::digest(label));
+        let start = db.len() - message.len();
+        db[start - 1] = 1;
+        db[start..].copy_from_slice(message);
+        mgf1_xor(seed, db);
+        mgf1_xor(db, seed);
+        let m = BigUInt4096::from_be_bytes(&em).unwrap();
+        Ok(Self::to_bytes(&self.public_op(&m), k))
+    }
+
+    /// RSASSA-PSS verification of a signature with a salt as long as the hash, as `sign` makes
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), RsaError> {
+        let s = self.parse(signature).ok_or(RsaError::Verification)?;
+        let m = self.public_op(&s);
+        let em_bits = self.n.bits() - 1;
+        let em_len = em_bits.div_ceil(8) as usize;
+        if m.bits() > em_bits || em_len < 2 * HASH_LEN + 2 {
+            return Err(RsaError::Verification);
+        }
+        // EM = maskedDB || H || 0xbc, with DB = PS || 0x01 || salt
+        let mut em = Self::to_bytes(&m, em_len);
+        if em[em_len - 1] != 0xbc {
+            return Err(RsaError::Verification);
+        }
+        let (db, h) = em[..em_len - 1].split_at_mut(em_len - HASH_LEN - 1);
+        mgf1_xor(h, db);
+        db[0] &= 0xff >> (8 * em_len as u32 - em_bits);
+        let separator = db.len() - HASH_LEN - 1;
+        if db[..separator].iter().any(|&byte| byte != 0) || db[separator] != 1 {
+            return Err(RsaError::Verification);
+        }
+        let expected = Sha256::new()
+            .chain_update([0u8; 8])
+            .chain_update(Sha256::digest(message))
+            .chain_update(&db[separator + 1..])
+            .finalize();
+        if bool::from(expected.ct_eq(h)) {
+            Ok(())
+        } else {
+            Err(RsaError::Verification)
+        }
+    }
+}
+
+impl RsaPrivateKey {
+    /// generates a key pair with a modulus of `bits` bits, 2048 or 4096, and `e = 65537`
+    pub fn generate(bits: u32, rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        assert!(bits == 2048 || bits == 4096, "Unsupported key size");
+        let e = BigUInt2048::new(&[PUBLIC_EXPONENT]);
+        // e is prime, so it is coprime to p - 1 unless it divides it
+        let mut random_prime = || loop {
+            let prime = BigUInt2048::random_prime(bits / 2, rng);
+            if !(&prime % &e).is_one() {
+                return prime;
+            }
+        };
+        loop {
+            let p = random_prime();
+            let q = random_prime();
+            if p == q {
+                continue;
+            }
+            let n = p.widening_mul(&q);
+            let e = widen(&e);
+            // d = e^(-1) mod lcm(p - 1, q - 1)
+            let one = BigUInt2048::new(&[1u64]);
+            let lambda = widen(&(&p - &one)).lcm(&widen(&(&q - &one)));
+            let d = e.mod_inverse(&lambda).unwrap();
+            if let Ok(key) =
+                RsaPublicKey::new(n, e).and_then(|public| Self::from_primes(public, d, p, q))
+            {
+                return key;
+            }
+        }
+    }
+
+    /// builds a private key from its modulus, exponents and prime factors, checking that
+    /// they are prime and consistent and precomputing the CRT values
+    pub fn from_components(
+        n: BigUInt4096,
+        e: BigUInt4096,
+        d: BigUInt4096,
+        p: BigUInt4096,
+        q: BigUInt4096,
+    ) -> Result<Self, RsaError> {
+        let public = RsaPublicKey::new(n, e)?;
+        if p.bits() > 2048 || q.bits() > 2048 || p.bits() < 2 || q.bits() < 2 {
+            return Err(RsaError::InvalidKey);
+        }
+        let (p, q) = (narrow(&p), narrow(&q));
+        if p.widening_mul::<64>(&q) != public.n
+            || !p.is_probable_prime(PRIME_ROUNDS)
+            || !q.is_probable_prime(PRIME_ROUNDS)
+        {
+            return Err(RsaError::InvalidKey);
+        }
+        Self::from_primes(public, d, p, q)
+    }
+
+    /// `from_components` for factors already known to be primes whose product is the modulus
+    fn from_primes(
+        public: RsaPublicKey,
+        d: BigUInt4096,
+        p: BigUInt2048,
+        q: BigUInt2048,
+    ) -> Result<Self, RsaError> {
+        // d must invert e modulo both p - 1 and q - 1
+        let one = BigUInt2048::new(&[1u64]);
+        let crt_exponent = |prime: &BigUInt2048| {
+            let order = prime - &one;
+            let exponent = narrow(&(&d % widen(&order)));
+            let e = narrow(&(&public.e % widen(&order)));
+            (narrow(&(e.widening_mul::<64>(&exponent) % widen(&order))) == one).then_some(exponent)
+        };
+        let dp = crt_exponent(&p).ok_or(RsaError::InvalidKey)?;
+        let dq = crt_exponent(&q).ok_or(RsaError::InvalidKey)?;
+        let qinv = q.mod_inverse(&p).ok_or(RsaError::InvalidKey)?;
+        Ok(RsaPrivateKey {
+            public,
+            d,
+            p,
+            q,
+            dp,
+            dq,
+            qinv,
+        })
+    }
+
+    /// public half of the key
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public
+    }
+
+    /// private exponent
+    pub fn d(&self) -> &BigUInt4096 {
+        &self.d
+    }
+
+    /// prime factors `p` and `q`
+    pub fn primes(&self) -> (&BigUInt2048, &BigUInt2048) {
+        (&self.p, &self.q)
+    }
+
+    /// raw private operation `value^d mod n`, blinding `value` with a random `r` from the
+    /// operating system's generator around the CRT
+    fn private_op(&self, value: &BigUInt4096) -> Result<BigUInt4096, RsaError> {
+        let n = &self.public.n;
+        let ctx = MontgomeryContext::new(n).unwrap();
+        let (r, r_inv) = loop {
+            let r = BigUInt4096::random_below(n, &mut OsRng);
+            if let Some(r_inv) = r.mod_inverse(n) {
+                break (r, r_inv);
+            }
+        };
+        // (value * r^e)^d = value^d * r, and a Montgomery product with a value in Montgomery
+        // form is a plain product
+        let blinded = ctx.mul(&ctx.to_montgomery(value), &self.public.public_op(&r));
+        let m = self.crt_op(&blinded)?;
+        Ok(ctx.mul(&ctx.to_montgomery(&m), &r_inv))
+    }
+
+    /// `value^d mod n` by the CRT, checked against the public key
+    fn crt_op(&self, value: &BigUInt4096) -> Result<BigUInt4096, RsaError> {
+        let (p, q) = (widen(&self.p), widen(&self.q));
+        let m1 = narrow(&(value % &p)).ct_modpow(&self.dp, &self.p);
+        let m2 = narrow(&(value % &q)).ct_modpow(&self.dq, &self.q);
+        // h = qinv * (m1 - m2) mod p, then m = m2 + h * q
+        let diff = narrow(&((&p - widen(&m2) % &p + widen(&m1)) % &p));
+        let h = narrow(&(self.qinv.widening_mul::<64>(&diff) % &p));
+        let m = widen(&m2) + h.widening_mul(&self.q);
+        if &self.public.public_op(&m) != value {
+            return Err(RsaError::Fault);
+        }
+        Ok(m)
+    }
+
+    /// RSAES-OAEP decryption with the given label; every padding failure gives the same error
+    pub fn decrypt(&self, ciphertext: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.public.size();
+        if k < 2 * HASH_LEN + 2 {
+            return Err(RsaError::Decryption);
+        }
+        let c = self.public.parse(ciphertext).ok_or(RsaError::Decryption)?;
+        let mut em = RsaPublicKey::to_bytes(&self.private_op(&c)?, k);
+        let leading = em[0];
+        let (seed, db) = em[1..].split_at_mut(HASH_LEN);
+        mgf1_xor(db, seed);
+        mgf1_xor(seed, db);
+        // check the padding without branching on it, to avoid a Manger-style oracle
+        let mut good = leading.ct_eq(&0) & db[..HASH_LEN].ct_eq(&Sha256::digest(label)[..]);
+        let mut looking = Choice::from(1);
+        let mut start = 0u32;
+        for (i, byte) in db[HASH_LEN..].iter().enumerate() {
+            let is_one = byte.ct_eq(&1);
+            start.conditional_assign(&(i as u32 + 1), looking & is_one);
+            good &= !looking | is_one | byte.ct_eq(&0);
+            looking &= !is_one;
+        }
+        good &= !looking;
+        if bool::from(good) {
+            Ok(db[HASH_LEN + start as usize..].to_vec())
+        } else {
+            Err(RsaError::Decryption)
+        }
+    }
+
+    /// RSASSA-PSS signature of `message` with a salt as long as the hash
+    pub fn sign(
+        &self,
+        message: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<Vec<u8>, RsaError> {
+        let mut salt = [0u8; HASH_LEN];
+        rng.fill_bytes(&mut salt);
+        self.sign_with_salt(message, &salt)
+    }
+
+    /// RSASSA-PSS signature with an explicit salt
+    fn sign_with_salt(&self, message: &[u8], salt: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let em_bits = self.public.n.bits() - 1;
+        let em_len = em_bits.div_ceil(8) as usize;
+        // EM = maskedDB || H || 0xbc, with DB = PS || 0x01 || salt
+        let h = Sha256::new()
+            .chain_update([0u8; 8])
+            .chain_update(Sha256::digest(message))
+            .chain_update(salt)
+            .finalize();
+        let mut em = vec![0u8; em_len];
+        let (db, rest) = em.split_at_mut(em_len - HASH_LEN - 1);
+        let start = db.len() - salt.len();
+        db[start - 1] = 1;
+        db[start..].copy_from_slice(salt);
+        mgf1_xor(&h, db);
+        db[0] &= 0xff >> (8 * em_len as u32 - em_bits);
+        rest[..HASH_LEN].copy_from_slice(&h);
+        rest[HASH_LEN] = 0xbc;
+        let m = BigUInt4096::from_be_bytes(&em).unwrap();
+        let s = self.private_op(&m)?;
+        Ok(RsaPublicKey::to_bytes(&s, self.public.size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mgf1_xor, RsaError, RsaPrivateKey, RsaPublicKey, HASH_LEN};
+    use crate::random::TestRng;
+    use crate::{BigUInt2048, BigUInt4096};
+    use std::sync::OnceLock;
+
+    // vectors below were produced with OpenSSL 3.5 (genpkey, pkeyutl and dgst with SHA-256
+    // OAEP and PSS padding) on the message below
+    const MESSAGE: &[u8] = b"p34 rsa test message";
+
+    fn from_hex(hex: &str) -> BigUInt4096 {
+        BigUInt4096::from_str_radix(hex, 16).unwrap()
+    }
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // OpenSSL-generated 2048-bit key
+    fn build_key_2048() -> RsaPrivateKey {
+        RsaPrivateKey::from_components(
+            from_hex(concat!(
+                "f8b44215d1d8ba03fb5f466912d06fce5f3aa8df375469a44e21086d5aa270e49ea6538ae14cfa85",
+                "e2edb498f419087a2de3f387f75c384b9ee00728f4d535cc5e13605f2a875b5cc3b842e5e697fa30",
+                "78637bd09e27f05122fc77072573cd48c9f2575400536525b8fea82277961f302b8a43be13ec920b",
+                "1cc1703f4b122fb5e1f0e0e18023011348565d75b89969de118b99510d1868725b005bcf643810ea",
+                "49e13f797214f9eb768a40850ab90c88883468e7d5b3117ea00172df35c036b5c1f74fd3d838f5c9",
+                "b775a5df754508e3c11e36bba01e91105dde64e8c708f933b43849b7e80eaa85b434fc95f31a85ff",
+                "1dffb5cb92cc1ceb862fcb3d35a9e289",
+            )),
+            from_hex("10001"),
+            from_hex(concat!(
+                "38e920cd36eb91e76adc7ab37d1d6ad44de8d1af608d25ae763d6b5d00b636c0ae08300a51f38849",
+                "5e6c181d3f10426718fcb663d8e806aed140810aef79a199ae365f616f8d314c29324f46a60d8570",
+                "1580685b0cf1e5355fdce74ce0965ef5a6d69882ad9f67c029d4c63b3ae3c4a7c472db7d11b89b13",
+                "c565036f4c59b146495d5e3dcbe54f97a759a08efa5b102eb882cdca615af77196ccc45a9defa7c0",
+                "0f5ea20073f8bc959021228651c917ebdf4708df5842ee70214dd9143c07d3cdf262b09c62a0514b",
+                "a9e01abd549ffe9279265e432d31fbc03d86643299a8070ba8efbccdfd933d02181ae4d8cb095f4c",
+                "c8b651522091caa4757873d715dba60d",
+            )),
+            from_hex(concat!(
+                "fe592d4cc62ba790b787f0cfa13094d326f01cdbec535a64728b85283f395bc168d1d73d1230bef6",
+                "44a6e12be2319720c1046bb7d3b27d5ee8a11546b8f656cf2384c9af7bfc906185205cbcdfcca428",
+                "2c10f1af4f92e69964a2df9dd189d4fe1e8ddeb3b651368731d9df0587b09953553697b250987e74",
+                "1d4132cc33a8e18d",
+            )),
+            from_hex(concat!(
+                "fa51b2c8cd2f83ffaf1252c6e06530f8621cc028e3a6e406b10fae5f8326833776b6790700116c53",
+                "0e90f328365845c8aee296e422e46344780ccdcc39d20a94a3b842c16752ed12f61bcc9cb63b36d5",
+                "d98845904fdc2a975133cf42439cdb853f81391ebda2e612f2f3c6ce19dfd5118ec0cff16483b375",
+                "6e9c0324b4451fed",
+            )),
+        )
+        .unwrap()
+    }
+
+    // OpenSSL-generated 4096-bit key
+    fn build_key_4096() -> RsaPrivateKey {
+        RsaPrivateKey::from_components(
+            from_hex(concat!(
+                "e4791275b4a8e27b77b79840ba758eef9e1832be398fd4a484503b77709e2e1c981d894ac832e6fc",
+                "31919540159154e06cb843d90a6269e75288e9a9715ecda844160435efe9ba1b01fcba7063334fad",
+                "4a4984e5afbeb6294bb7b72ff46c8bdca204e1c7a2ace3a816231f8a9d41c82314a82320b78436ce",
+                "fe97299168afe9f6b90db74b71eeeb64f78e65164034bdca90cda92bf4f541f54a9c4ac9cda003cc",
+                "db57efb217e223cb811abea5b1bc27dadcefe63e99bd517ab2323eca0a1a7ac7f5937b97a4cdd388",
+                "a46b7f219ae7aaaa9dda581475079b6827363d202152d2b822cc401f96be8a2f3f5ab88bb5ffd6cf",
+                "9c276966730a8c5e10ac3d03329e5993c3f0e14474982fa8d2e6da483c4cab8aec6422a13053858d",
+                "0c006724b11b782f266a9b3b5db1e2f378464e7c5704d12bf4e662f9a749822f0d45b23ed06a003c",
+                "517988c50e5640fab5d6dbb4dac904fd7474fcd0b348469acaaf8e5f063e905c2f59a95ab2a3e824",
+                "c8b24d56f255c41ce3de24b6110787c294a1e6726686fbb1c61b0b3b293a5f9fc3c231065794ecb6",
+                "b8c061388aa17926884408b1658600d6e55a40978561c06cd76545226d2baa9af2f1118e97497125",
+                "3d6bdf811b6b0761807413932ea584b5373fed13efc4e50e11efd2282f04bbe2ceef9bcbea28c8d7",
+                "8253d33a72c63a5c1a55d7feb9e344b9fe357dc946e9eef25d18e3579eed9907",
+            )),
+            from_hex("10001"),
+            from_hex(concat!(
+                "11d1f3ae4f44ab984512828341f2860f35d3335a02495aa4e9010d07ad8c0392b80bf8626727b93b",
+                "cb18a22883c623204cba8e92db817e3b21afe0c7163af66ad26cae8565220e21a9cfaedc5c906afd",
+                "86d6cc7bd1c50696455148ae2a855b89fe451d35ccaabed2f9c4618dc13843f0f5dd2b683e32c772",
+                "465f3f49c27d9365155d4c2cf69a53ad3b608037f268155ee5ae74d723330ca2f78a7b36d99d4a79",
+                "41a923dea332eb3c7b39bd21aab194a0e32ca9abbba2b83be15c3e937ef83e6d6195259b02aab47e",
+                "15924b87312587fa937757f9632b65b958f2f264e829cfefb6c9c6e7efd7b56ad153dfcdb3335ceb",
+                "87dacc6e1b8e4e11242c7051d33290a53fc7cce72bee4418248fd0f1e3e5add77c11f4d86de56420",
+                "ace92caa05f67ef979cced11cd1d0a8a15c6f9a295fe529c4b2cc307d5a40906274de34e7ab77a31",
+                "0f7d5ba755b16d4d111dc2e940831c8df976dd0888cfdc1b980542472d38385b4b0b4a51485ae46e",
+                "edfef6e9610ef10b7ae55dbbeed756f9886fdf23297deb9275386184fa96e45d8cc3610ec3714d53",
+                "e010af7cdb1b071c58db71b775ad49aa03bbee42dce99f31835e9ce87e2db553ac885a384ff4b173",
+                "adf991a55a7779beca5fbda5cf338eff1dc0b145b1857c670f586ee89c06e6aa7561b0af378cae23",
+                "5efbf3fb01bc48d2143eba94501fdf1d71697353ceda8356e7b1d04281520299",
+            )),
+            from_hex(concat!(
+                "f7f2160ac7971c295e277a1b4c806d95035854a6d0e7ece04c8986fd8746daffdc1fb77603fbc2d6",
+                "092038a4b37f468e5c95a11d65d198902ef3c2abb2ba825d9b5af6d6293c8f272ec4b8bb24c9c4a0",
+                "d1b52837f957f9935999ec5b84d041c466a65c8326c096a9883f6c77bfaad38175b92da413665384",
+                "3ce864f4f8eb17f812ffe7c3195675f9b21e4504c03267bfb7a2a5b40d301eb491491a13595bdbad",
+                "33a2c61ed951776403893d9a7a9ab5f87bc8e76edc8daf32dba7dc6d3fcc8fcac6a676f3d9558837",
+                "0e192334fdb5f0ae79edf755caa742b1f945cc151951613f8b2319381528fdef053d0b0077e06365",
+                "c5a1603faf07dea6c13d4f3a18939bdd",
+            )),
+            from_hex(concat!(
+                "ebe50d1579a613a9397c8aa485c7b6e1eea65c41c9048f92c536ed14af704dca4dc2ecc854580a28",
+                "0090d6a50eef332d017bddfe35f4c8571e5b0524337093c315161da481aac7f44b1d7caffc86eb3c",
+                "550c32500405db4dfbc480a77f641c91b43ce7f2f95fc3c6c0d01e3ce6e52121b7ec1308bf36e82d",
+                "13cb38fca75d9178fcef29cfb30321e6c8d34a91d59dbe73163066e2d49fb3857b4184aedf343e9b",
+                "179a18f4d0c40a0ab81ba704c79a587a5fb7a914a7115c210d9dc4cbf7f4ff54697220e4bcb21608",
+                "b2e2a059c5be75f0b9d6702565a44549ce64e11492c764eb87601f46aca51ab840607e2df3461c68",
+                "cfbf21bb3b50fc0a9e357e6e46f8fc33",
+            )),
+        )
+        .unwrap()
+    }
+
+    // the keys above, built once per run since checking their primes is slow
+    fn key_2048() -> &'static RsaPrivateKey {
+        static KEY: OnceLock<RsaPrivateKey> = OnceLock::new();
+        KEY.get_or_init(build_key_2048)
+    }
+
+    fn key_4096() -> &'static RsaPrivateKey {
+        static KEY: OnceLock<RsaPrivateKey> = OnceLock::new();
+        KEY.get_or_init(build_key_4096)
+    }
+
+    const C2048: &str = concat!(
+        "c45bc8846845ce47ddeb7f4428a4424151f9f25b7ba03892841d742a51839fd94fd63fdf8b93fde1",
+        "3a7066d8aebc6ae40278905b7bb4fb732bdf64a3490b0160abce84d9a7811a4fc12f5d8d16a67b57",
+        "b69c4a83d3333ff746599edb569cf61968f98a105098f5de9ab0de116d56e618d7e2a6def97e5b7a",
+        "59d9131395c65fd28875e60f8501bccf23510f2b9f7493c40c55967e5b81df0e9a2e52c06d72bd18",
+        "12f4697947520013375871288b37c307500432cc4e593727a0878c745bd26fd6251716a1be18612e",
+        "264b2c8d15bc6cfa04e16e6de863f79cb68ec7137e3d9e1f969150438b8b5fa96861a550d2993fed",
+        "89b58c406bb5476c66ea98716f0ccb32",
+    );
+
+    const C4096: &str = concat!(
+        "025ff8c36dbca114ebf1b35b88f2857ab789653dbd13afe2b56b4814cd5eb17205690751d315d028",
+        "6f33e9129c91fba121f3e24327726e54eb1a8482185c1495874c5f30eddf2031244703bd3f316647",
+        "9f4fe2560234404c2fdbc5026325418bdb7dcb1e2b5ee9bc3e195701f0c8e3e4ef396cb5bd1ac3a7",
+        "75337ffd53121e748066c805de0418df60fe10eb7ac53a97cbc71043e238124eb34059d49362660d",
+        "53089ae35d95bd3a9800113c834cdaebd6e7b6490cc25f27e898ad5205c87b4c859237bccd739450",
+        "9dcda7504f675d75783e6f8e9422818347b54fffc3a38f6bd51b6820e9c7a32f0be3e81442e9e860",
+        "4abfe4fb4d3fb24cd9bad93bff506d5050b48dcb9e375ee2f7f3d85761093f35ef39522c3d4db9bf",
+        "42493114d6fd9524de5bdb778de457391802d080f257b3bea38831a972364823a2549c43b2126430",
+        "e4ecafb99f03ea4cd4d9b076d7ac1a0a970dd2ef4de1e6998f41491549bdead65e6a8ddab19c3984",
+        "73546f524cabb2f3f30f6f8c96b9c30796c28c884a74962caba27d83e90c87ba94f0135cbbeb35a4",
+        "fc88f59ca909177ebb6cbde366311a9ff4dc5cefa631dc4dd22f33fdbac0a7d58e7b8c36d0919f57",
+        "a18b2c52da9a23aee58a6cdcdf7344e20e4f12cf85e1a64df4172414752b64ebeb82b933be5e93f4",
+        "84058e5b03f3fe421c42fd7d81796b57ccfc787e44e3d3ae291ee898486c4d32",
+    );
+
+    const S32: &str = concat!(
+        "4fcc14a935a8ca76d7466ff465bdf82f51a332abcfd901fdd551a91edf74249d7322636fc0b610cb",
+        "b63cbd5aca36d95fe1c02bd77e50b9cbf270bf2977a206e0d1a814f0c160b499b2cb15e4b474962a",
+        "18e30e970d8d56c2cd2c74f7f2cff92cee201eef315939f063c5a95fe0e027d86da61b2924bcdb88",
+        "112bc395d2eb6798e5fafd83133881a6bdf92eb7f5ef5ca729ef7675377561935ce81cfe5eccb02c",
+        "2f2fab5ebc10f1bea41bf314d3986062076fc8c0ce8e29581c6705098576c38647e0b18814b848f2",
+        "74f753599f5e9d824ed947d40d5fa1a24bbb056d26ce629228592adba4f53656ec0fef2c21912df7",
+        "d53e9f83e29d175cf515997d85cbb63f",
+    );
+
+    const S0: &str = concat!(
+        "86173eed8564d82ffe46f1c23aa64dfa50e61d73a0983ea1dfaf0fb833fa5d3df8f4cd61e4c24b47",
+        "2a5bd3ff55bc66d7432d6dde0fab346d073d08d133e36c391de981faf3ea6862db4f740d20a3a7a1",
+        "1f1068ac332d575b515be6417d2336920347a9cbd81fa88b1ef7ae7cfd2e695c5bf4927619b86061",
+        "dff7bcc8c71008fdd00bd69094e2fac68ad9e9022f0b327c0386e05c90594b7605310ce66fff0913",
+        "67860bde183b3ef241066d915a6098d8055b3fb3a6b079b68c77646997a99c4f533bf830372dd4ed",
+        "1032bbbf9f8b9e1e120148d1c59bd30b47d9205aede5162699d2773e8f7bd65e7bde9da015e357a0",
+        "d6401163c6d07a165289b18dabe1c453",
+    );
+
+    const S4096: &str = concat!(
+        "4790fd0334f449d9137e8a6b91c55f2fb3006a9fb2b9b07f6c5eb15c0970de4d772e6285e5464f4c",
+        "e0f38959f97ae486d6490625b50175d62f1f44388c09f968c384a6b7533804afefc172632d03cc25",
+        "7e2c330f053ac7e95b2eb2395ed2c1bfa19702f8dc468d0f7ee56084a0d459c07f3697d82aa79b63",
+        "b9e7327284d908c1094ec3186063df1463300887b4d00c0acc183c92aefa01739301489cf40633a8",
+        "8a4e06f2c16e1fdb1ad5105476c456bf1b6a473fd47d2fdebe84feff8f96532e2a082f93d46b8be4",
+        "7e467611a066a64f4bb1b42c1b8318cff5fa1ebf146d2e00aaaaca1e69e90eafbe9242534d20178f",
+        "bee3049b8ebdf1a2e9e1094e10cc3f8ba07050322dc531c77501432db94e6867aef6b7cddc2a5363",
+        "525977e6712a193c1a70797084db436363cdcfc41891cfe9fbfc1dc05f523406941c0bb6d7c91189",
+        "5ee9d9ca6773609242734f157f4bce49382bcad943d1cfa5a1b8166409a2ee7f1c4b73648618c205",
+        "2dff1e1162b0fa7c431c8b4c616eb5f3d18f67c510c05a4042e76aca45f95f93394ea07a69677e0d",
+        "a946615ea13b4128088cfff1c90a28feeaee34451bcf6a4104a5ba68c746ea27ea9cbfa467dd6d12",
+        "a74e12e869b367a2b1c23d2082ac7e6880d0fe79a65accdbfac8e960e0984812e2a0ccf4c6e10e60",
+        "ffc201fd34c7a3524d4613508c424275d00e99b2280b3ed890e895bc94c0cb3a",
+    );
+
+    // test OAEP decryption of OpenSSL ciphertexts, with and without a label
+    #[test]
+    fn oaep_decrypt_vector_test() {
+        let key = key_2048();
+        assert_eq!(key.decrypt(&bytes(C2048), b"").unwrap(), MESSAGE);
+        assert_eq!(
+            key.decrypt(&bytes(C2048), b"label"),
+            Err(RsaError::Decryption)
+        );
+        let key = key_4096();
+        assert_eq!(key.decrypt(&bytes(C4096), b"label").unwrap(), MESSAGE);
+        assert_eq!(key.decrypt(&bytes(C4096), b""), Err(RsaError::Decryption));
+    }
+
+    // test PSS verification of OpenSSL signatures, and the deterministic empty-salt signature,
+    // which verification turns down for its salt length
+    #[test]
+    fn pss_vector_test() {
+        let key = key_2048();
+        let public = key.public_key();
+        assert_eq!(public.verify(MESSAGE, &bytes(S32)), Ok(()));
+        assert_eq!(
+            public.verify(MESSAGE, &bytes(S0)),
+            Err(RsaError::Verification)
+        );
+        assert_eq!(key.sign_with_salt(MESSAGE, &[]), Ok(bytes(S0)));
+        assert_eq!(
+            public.verify(b"p34 rsa test messagE", &bytes(S32)),
+            Err(RsaError::Verification)
+        );
+        let mut tampered = bytes(S32);
+        tampered[100] ^= 1;
+        assert_eq!(
+            public.verify(MESSAGE, &tampered),
+            Err(RsaError::Verification)
+        );
+        assert_eq!(
+            public.verify(MESSAGE, &tampered[1..]),
+            Err(RsaError::Verification)
+        );
+        let key = key_4096();
+        assert_eq!(key.public_key().verify(MESSAGE, &bytes(S4096)), Ok(()));
+        assert_eq!(
+            key_2048().public_key().verify(MESSAGE, &bytes(S4096)),
+            Err(RsaError::Verification)
+        );
+    }
+
+    // Wycheproof vector sets from github.com/C2SP/wycheproof (Apache-2.0), copied unchanged
+    const WYCHEPROOF_OAEP: &str =
+        include_str!("../tests/data/rsa_oaep_2048_sha256_mgf1sha256_test.json");
+    const WYCHEPROOF_PSS: &str =
+        include_str!("../tests/data/rsa_pss_2048_sha256_mgf1_32_test.json");
+
+    fn test_groups(json: &str) -> Vec<serde_json::Value> {
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        value["testGroups"].as_array().unwrap().clone()
+    }
+
+    fn field<'a>(value: &'a serde_json::Value, name: &str) -> &'a str {
+        value[name].as_str().unwrap()
+    }
+
+    // test OAEP against the Wycheproof SHA-256 set: valid ciphertexts decrypt to their
+    // message and are reproduced exactly by encryption with the same seed, and invalid
+    // ones are rejected
+    #[test]
+    fn wycheproof_oaep_test() {
+        for group in test_groups(WYCHEPROOF_OAEP) {
+            let components = &group["privateKey"];
+            let key = RsaPrivateKey::from_components(
+                from_hex(field(components, "modulus")),
+                from_hex(field(components, "publicExponent")),
+                from_hex(field(components, "privateExponent")),
+                from_hex(field(components, "prime1")),
+                from_hex(field(components, "prime2")),
+            )
+            .unwrap();
+            for test in group["tests"].as_array().unwrap() {
+                let id = &test["tcId"];
+                let (message, label) = (bytes(field(test, "msg")), bytes(field(test, "label")));
+                let ciphertext = bytes(field(test, "ct"));
+                let decrypted = key.decrypt(&ciphertext, &label);
+                if field(test, "result") == "invalid" {
+                    assert_eq!(decrypted, Err(RsaError::Decryption), "tcId {}", id);
+                    continue;
+                }
+                assert_eq!(decrypted, Ok(message.clone()), "tcId {}", id);
+                // unmask the seed the vector was made with and encrypt again from it
+                let c = key.public_key().parse(&ciphertext).unwrap();
+                let mut em = RsaPublicKey::to_bytes(&key.private_op(&c).unwrap(), 256);
+                let (masked_seed, db) = em[1..].split_at_mut(HASH_LEN);
+                mgf1_xor(db, masked_seed);
+                let seed: [u8; HASH_LEN] = (*masked_seed).try_into().unwrap();
+                assert_eq!(
+                    key.public_key().encrypt_with_seed(&message, &label, &seed),
+                    Ok(ciphertext),
+                    "tcId {}",
+                    id
+                );
+            }
+        }
+    }
+
+    // test PSS verification against the Wycheproof SHA-256 set with 32-byte salts
+    #[test]
+    fn wycheproof_pss_test() {
+        for group in test_groups(WYCHEPROOF_PSS) {
+            let components = &group["publicKey"];
+            let key = RsaPublicKey::new(
+                from_hex(field(components, "modulus")),
+                from_hex(field(components, "publicExponent")),
+            )
+            .unwrap();
+            for test in group["tests"].as_array().unwrap() {
+                let expected = match field(test, "result") {
+                    "valid" => Ok(()),
+                    _ => Err(RsaError::Verification),
+                };
+                let (message, signature) = (bytes(field(test, "msg")), bytes(field(test, "sig")));
+                assert_eq!(
+                    key.verify(&message, &signature),
+                    expected,
+                    "tcId {}",
+                    test["tcId"]
+                );
+            }
+        }
+    }
+
+    // test encryption and signing round trips, and rejection of altered inputs
+    #[test]
+    fn round_trip_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let key = key_2048();
+        let public = key.public_key();
+        for message in [&b""[..], MESSAGE, &[0xa5; 190]] {
+            let ciphertext = public.encrypt(message, b"label", &mut rng).unwrap();
+            assert_eq!(ciphertext.len(), 256);
+            assert_eq!(key.decrypt(&ciphertext, b"label").unwrap(), message);
+            let mut tampered = ciphertext.clone();
+            tampered[200] ^= 0x80;
+            assert_eq!(key.decrypt(&tampered, b"label"), Err(RsaError::Decryption));
+            let signature = key.sign(message, &mut rng).unwrap();
+            assert_eq!(public.verify(message, &signature), Ok(()));
+        }
+        assert_eq!(
+            public.encrypt(&[0; 191], b"", &mut rng),
+            Err(RsaError::MessageTooLong)
+        );
+        // randomised padding gives different ciphertexts for the same message
+        assert_ne!(
+            public.encrypt(MESSAGE, b"", &mut rng),
+            public.encrypt(MESSAGE, b"", &mut rng)
+        );
+    }
+
+    // test that inconsistent key components are rejected
+    #[test]
+    fn from_components_test() {
+        let key = key_2048();
+        let (n, e, d) = (key.public_key().n(), key.public_key().e(), key.d());
+        let (p, q) = key.primes();
+        let (p, q) = (super::widen(p), super::widen(q));
+        let two = BigUInt4096::new(&[2u64]);
+        let invalid = [
+            (n + &two, e, d, &p, &q),
+            (n.clone(), e, &(d + &two), &p, &q),
+            (n.clone(), &two, d, &p, &q),
+            (n.clone(), e, d, &p, &p),
+        ];
+        for (n, e, d, p, q) in invalid {
+            assert_eq!(
+                RsaPrivateKey::from_components(n, e.clone(), d.clone(), p.clone(), q.clone()).err(),
+                Some(RsaError::InvalidKey)
+            );
+        }
+        let rebuilt = RsaPrivateKey::from_components(n.clone(), e.clone(), d.clone(), q, p);
+        assert!(rebuilt.is_ok());
+        // the modulus has no small factors and, paired with the prime 2^127 - 1 and a d that
+        // inverts e modulo both factors less one, passes every check but primality
+        let one = BigUInt4096::new(&[1u64]);
+        let prime = BigUInt4096::new(&[u64::MAX, u64::MAX >> 1]);
+        let d = e.mod_inverse(&(n - &one).lcm(&(&prime - &one))).unwrap();
+        let product = super::narrow(n).widening_mul(&super::narrow(&prime));
+        assert_eq!(
+            RsaPrivateKey::from_components(product, e.clone(), d, n.clone(), prime).err(),
+            Some(RsaError::InvalidKey)
+        );
+    }
+
+    // test that debug output leaves out the secret values
+    #[test]
+    fn debug_test() {
+        let key = key_2048();
+        let debug = format!("{:?}", key);
+        assert!(debug.starts_with("RsaPrivateKey { public: RsaPublicKey { n: "));
+        assert!(debug.ends_with(".. }"));
+        assert_eq!(debug.matches("chunks").count(), 2);
+    }
+
+    // test that the blinded CRT operation agrees with exponentiation by d, whatever the
+    // blinding factor drawn
+    #[test]
+    fn private_op_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let key = key_2048();
+        let n = key.public_key().n();
+        for _ in 0..3 {
+            let value = BigUInt4096::random_below(n, &mut rng);
+            let expected = value.modpow(key.d(), n);
+            assert_eq!(key.private_op(&value), Ok(expected.clone()));
+            assert_eq!(key.private_op(&value), Ok(expected));
+        }
+    }
+
+    // test that a faulty private operation gives an error instead of its result
+    #[test]
+    fn fault_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let mut key = key_2048().clone();
+        let ciphertext = key.public_key().encrypt(MESSAGE, b"", &mut rng).unwrap();
+        key.dp = &key.dp + BigUInt2048::new(&[1u64]);
+        assert_eq!(key.decrypt(&ciphertext, b""), Err(RsaError::Fault));
+        assert_eq!(key.sign(MESSAGE, &mut rng), Err(RsaError::Fault));
+    }
+
+    // test key generation gives a working key of the requested size
+    #[test]
+    fn generate_test() {
+        let mut rng = TestRng(0x9e3779b97f4a7c15);
+        let key = RsaPrivateKey::generate(2048, &mut rng);
+        let public = key.public_key();
+        assert_eq!(public.n().bits(), 2048);
+        assert_eq!(public.e(), &BigUInt4096::new(&[65537u64]));
+        let ciphertext = public.encrypt(MESSAGE, b"", &mut rng).unwrap();
+        assert_eq!(key.decrypt(&ciphertext, b"").unwrap(), MESSAGE);
+        let signature = key.sign(MESSAGE, &mut rng).unwrap();
+        assert_eq!(public.verify(MESSAGE, &signature), Ok(()));
+    }
+}
@@ -0,0 +1,465 @@
+//! Signed integers in sign-magnitude form on top of `BigUInt`.
+//!
+//! The `/` and `%` operators truncate towards zero like Rust's primitive integers, so the
+//! remainder takes the sign of the dividend; `div_euclid` and `rem_euclid` give the
+//! Euclidean convention with a remainder that is never negative.
+
+use crate::BigUInt;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// signed integer with a `BigUInt<LIMBS>` magnitude; zero is never negative
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct BigInt<const LIMBS: usize> {
+    negative: bool,
+    magnitude: BigUInt<LIMBS>,
+}
+
+pub type BigInt256 = BigInt<4>;
+pub type BigInt512 = BigInt<8>;
+pub type BigInt1024 = BigInt<16>;
+pub type BigInt2048 = BigInt<32>;
+pub type BigInt4096 = BigInt<64>;
+pub type BigInt8192 = BigInt<128>;
+
+/// error returned when converting a negative `BigInt` to a `BigUInt`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegativeValueError;
+
+impl fmt::Display for NegativeValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "negative value cannot be converted to an unsigned integer"
+        )
+    }
+}
+
+impl std::error::Error for NegativeValueError {}
+
+impl<const LIMBS: usize> BigInt<LIMBS> {
+    /// builds a value from a sign and a magnitude; a zero magnitude gives zero whatever the sign
+    pub fn from_parts(negative: bool, magnitude: BigUInt<LIMBS>) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.negative && !self.magnitude.is_zero()
+    }
+
+    /// absolute value as a `BigUInt`
+    pub fn magnitude(&self) -> &BigUInt<LIMBS> {
+        &self.magnitude
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    /// -1, 0 or 1 according to the sign
+    pub fn signum(&self) -> Self {
+        if self.is_zero() {
+            Self::default()
+        } else {
+            Self::from_parts(self.negative, BigUInt::new(&[1u64]))
+        }
+    }
+
+    /// sum of `self` and the value with the given sign and magnitude
+    fn add_parts(&self, negative: bool, magnitude: &BigUInt<LIMBS>) -> Self {
+        if self.negative == negative {
+            return Self::from_parts(negative, &self.magnitude + magnitude);
+        }
+        if &self.magnitude >= magnitude {
+            Self::from_parts(self.negative, &self.magnitude - magnitude)
+        } else {
+            Self::from_parts(negative, magnitude - &self.magnitude)
+        }
+    }
+
+    fn mul_parts(&self, other: &Self) -> Self {
+        Self::from_parts(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+
+    /// truncating division: the quotient rounds towards zero and the remainder has the sign
+    /// of `self`; panics on division by zero
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (quot, rem) = self.magnitude.div_rem(&other.magnitude);
+        (
+            Self::from_parts(self.negative != other.negative, quot),
+            Self::from_parts(self.negative, rem),
+        )
+    }
+
+    /// Euclidean division: the remainder is in `[0, |other|)`; panics on division by zero
+    pub fn div_rem_euclid(&self, other: &Self) -> (Self, Self) {
+        let (quot, rem) = self.div_rem(other);
+        if !rem.negative {
+            return (quot, rem);
+        }
+        let one = Self::from_parts(false, BigUInt::new(&[1u64]));
+        let quot = if other.negative {
+            quot + one
+        } else {
+            quot - one
+        };
+        (quot, rem.add_parts(false, &other.magnitude))
+    }
+
+    pub fn div_euclid(&self, other: &Self) -> Self {
+        self.div_rem_euclid(other).0
+    }
+
+    pub fn rem_euclid(&self, other: &Self) -> Self {
+        self.div_rem_euclid(other).1
+    }
+}
+
+impl<const LIMBS: usize> From<BigUInt<LIMBS>> for BigInt<LIMBS> {
+    fn from(magnitude: BigUInt<LIMBS>) -> Self {
+        Self::from_parts(false, magnitude)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigInt<LIMBS>> for BigUInt<LIMBS> {
+    type Error = NegativeValueError;
+
+    fn try_from(value: BigInt<LIMBS>) -> Result<Self, Self::Error> {
+        if value.negative {
+            Err(NegativeValueError)
+        } else {
+            Ok(value.magnitude)
+        }
+    }
+}
+
+impl<const LIMBS: usize> Ord for BigInt<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for BigInt<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> fmt::Display for BigInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Neg for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Neg for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Add<BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn add(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Add<&BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn add(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Add<BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn add(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Add<&BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn add(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Sub<BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn sub(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(!other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Sub<&BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn sub(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(!other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Sub<BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn sub(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(!other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Sub<&BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn sub(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.add_parts(!other.negative, &other.magnitude)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Mul<BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn mul(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.mul_parts(&other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Mul<&BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn mul(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.mul_parts(other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Mul<BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn mul(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.mul_parts(&other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Mul<&BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn mul(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.mul_parts(other)
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn div(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(&other).0
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<&BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn div(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(other).0
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn div(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(&other).0
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Div<&BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn div(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(other).0
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn rem(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(&other).1
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<&BigInt<LIMBS>> for BigInt<LIMBS> {
+    type Output = Self;
+
+    fn rem(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(other).1
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn rem(self, other: BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(&other).1
+    }
+}
+
+impl<const LIMBS: usize> std::ops::Rem<&BigInt<LIMBS>> for &BigInt<LIMBS> {
+    type Output = BigInt<LIMBS>;
+
+    fn rem(self, other: &BigInt<LIMBS>) -> Self::Output {
+        self.div_rem(other).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NegativeValueError;
+    use crate::{BigInt256, BigInt4096, BigUInt256, BigUInt4096};
+
+    fn int(value: i64) -> BigInt256 {
+        BigInt256::from_parts(value < 0, BigUInt256::new(&[value.unsigned_abs()]))
+    }
+
+    // test addition, subtraction and multiplication against i64 across sign combinations
+    #[test]
+    fn add_sub_mul_test() {
+        for a in -12i64..=12 {
+            for b in -12i64..=12 {
+                let (x, y) = (int(a), int(b));
+                assert_eq!(x.clone() + y.clone(), int(a + b));
+                assert_eq!(x.clone() - &y, int(a - b));
+                assert_eq!(&x * y.clone(), int(a * b));
+                assert_eq!(&x + &y - &y, x);
+            }
+        }
+    }
+
+    // test truncating and Euclidean division against i64
+    #[test]
+    fn div_rem_test() {
+        for a in -30i64..=30 {
+            for b in (-7i64..=7).filter(|&b| b != 0) {
+                let (x, y) = (int(a), int(b));
+                assert_eq!(x.clone() / y.clone(), int(a / b));
+                assert_eq!(x.clone() % &y, int(a % b));
+                assert_eq!(&x / &y, int(a / b));
+                assert_eq!(&x % y.clone(), int(a % b));
+                assert_eq!(x.div_euclid(&y), int(a.div_euclid(b)));
+                assert_eq!(x.rem_euclid(&y), int(a.rem_euclid(b)));
+                assert_eq!(
+                    x.div_rem_euclid(&y),
+                    (int(a.div_euclid(b)), int(a.rem_euclid(b)))
+                );
+            }
+        }
+    }
+
+    // test division by zero panics
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn div_zero_test() {
+        let _ = int(5) / int(0);
+    }
+
+    // test negation, abs, signum and that zero is never negative
+    #[test]
+    fn sign_test() {
+        for a in -3i64..=3 {
+            assert_eq!(-int(a), int(-a));
+            assert_eq!(-&int(a), int(-a));
+            assert_eq!(int(a).abs(), int(a.abs()));
+            assert_eq!(int(a).signum(), int(a.signum()));
+            assert_eq!(int(a).is_negative(), a < 0);
+            assert_eq!(int(a).is_positive(), a > 0);
+        }
+        let zero = BigInt256::from_parts(true, BigUInt256::default());
+        assert!(!zero.is_negative());
+        assert_eq!(zero, BigInt256::default());
+        assert_eq!(-BigInt256::default(), BigInt256::default());
+        assert_eq!(int(5) - int(5), BigInt256::default());
+        assert!(!(int(-5) + int(5)).is_negative());
+    }
+
+    // test ordering against i64
+    #[test]
+    fn cmp_test() {
+        for a in -5i64..=5 {
+            for b in -5i64..=5 {
+                assert_eq!(int(a).cmp(&int(b)), a.cmp(&b));
+            }
+        }
+    }
+
+    // test conversions to and from BigUInt and formatting
+    #[test]
+    fn conversion_display_test() {
+        let magnitude = BigUInt4096::new(&[0, 0, 0, 1]);
+        let positive = BigInt4096::from(magnitude.clone());
+        assert_eq!(
+            BigUInt4096::try_from(positive.clone()),
+            Ok(magnitude.clone())
+        );
+        assert_eq!(
+            BigUInt4096::try_from(-positive.clone()),
+            Err(NegativeValueError)
+        );
+        assert_eq!((-&positive).magnitude(), &magnitude);
+        assert_eq!(format!("{}", int(-42)), "-42");
+        assert_eq!(format!("{:+}", int(42)), "+42");
+        assert_eq!(format!("{:>5}", int(-7)), "   -7");
+        assert_eq!(
+            format!("{}", -positive),
+            "-6277101735386680763835789423207666416102355444464034512896"
+        );
+    }
+
+    // test full-width magnitudes, where the sign handling must not overflow
+    #[test]
+    fn full_width_test() {
+        let max = BigInt256::from(!BigUInt256::default());
+        let one = int(1);
+        assert_eq!(-&max + &max, BigInt256::default());
+        assert_eq!(-&max - -&max, BigInt256::default());
+        assert_eq!((&max - &one) - &max, int(-1));
+        assert_eq!(-&max + (&max - &one), int(-1));
+        assert_eq!((-&max).div_rem(&int(2)), (-(&max / int(2)), int(-1)));
+        assert_eq!((-&max).rem_euclid(&int(2)), int(1));
+        assert_eq!((-&max).div_euclid(&max), int(-1));
+        assert_eq!((-&max).div_euclid(&(-&max)), int(1));
+    }
+}
@@ -1,25 +1,71 @@
 use base16ct::mixed;
-use p72::decrypt;
-use std::{env, io};
+use p72::password::read_password;
+use p72::{decrypt, decrypt_with_password, Error};
+use std::env;
+use std::process::{self, ExitCode};
 
-fn main() -> std::io::Result<()> {
+const USAGE: &str = "Usage: ring_dec INPUT OUTPUT (KEY | --password [--password-fd FD])";
+
+/// prints `message` and the usage line, then exits with code 1
+fn usage(message: &str) -> ! {
+    eprintln!("ring_dec: {}", message);
+    eprintln!("{}", USAGE);
+    process::exit(1)
+}
+
+fn run() -> Result<(), Error> {
     let mut args = env::args();
 
     args.next().unwrap();
-    let input_path = &args.next().expect("Provide input path")[..];
-    let output_path = &args.next().expect("Provide output path")[..];
-    let key = &args.next().expect("Provide hex-encoded key")[..];
+    let input_path = &args.next().unwrap_or_else(|| usage("Provide input path"))[..];
+    let output_path = &args.next().unwrap_or_else(|| usage("Provide output path"))[..];
+
+    let mut key = None;
+    let mut password = false;
+    let mut fd = None;
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--password" => password = true,
+            "--password-fd" => {
+                fd = Some(
+                    args.next()
+                        .unwrap_or_else(|| usage("Missing value for --password-fd"))
+                        .parse()
+                        .unwrap_or_else(|_| usage("Invalid file descriptor")),
+                )
+            }
+            _ if key.is_none() && !arg.starts_with("--") => key = Some(arg),
+            _ => usage(&format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    if password && key.is_some() {
+        usage("Give either a key or --password, not both");
+    }
+    if !password && fd.is_some() {
+        usage("--password-fd needs --password");
+    }
 
     println!("Decrypting {} into {}...", input_path, output_path);
 
-    let mut buf = [0u8; 16];
-    let key_bytes = mixed::decode(key, &mut buf).unwrap();
-    decrypt(
-        input_path,
-        output_path,
-        key_bytes.try_into().map_err(|_| {
-            io::Error::new(io::ErrorKind::Other, "Wrong key length: 16 bytes expected")
-        })?,
-    )?;
-    Ok(())
+    if password {
+        let password = read_password(fd, false)?;
+        return decrypt_with_password(input_path, output_path, password.as_bytes());
+    }
+
+    let key = key.unwrap_or_else(|| usage("Provide hex-encoded key or --password"));
+    let mut buf = [0u8; 64];
+    let key_bytes =
+        mixed::decode(&key, &mut buf).unwrap_or_else(|_| usage("Invalid hex-encoded key"));
+    decrypt(input_path, output_path, key_bytes)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("ring_dec: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
 }
@@ -1,25 +1,106 @@
 use base16ct::mixed;
-use p72::encrypt;
-use std::{env, io};
+use p72::header::Algorithm;
+use p72::password::{read_password, DEFAULT_ITERATIONS, MAX_ITERATIONS, MIN_ITERATIONS};
+use p72::{encrypt, encrypt_with_password, Error};
+use std::env;
+use std::process::{self, ExitCode};
 
-fn main() -> std::io::Result<()> {
+const USAGE: &str = "Usage: ring_enc INPUT OUTPUT (KEY | --password [--password-fd FD] \
+                     [--iterations N]) [--algorithm NAME]";
+
+/// prints `message` and the usage line, then exits with code 1
+fn usage(message: &str) -> ! {
+    eprintln!("ring_enc: {}", message);
+    eprintln!("{}", USAGE);
+    process::exit(1)
+}
+
+fn run() -> Result<(), Error> {
     let mut args = env::args();
 
     args.next().unwrap();
-    let input_path = &args.next().expect("Provide input path")[..];
-    let output_path = &args.next().expect("Provide output path")[..];
-    let key = &args.next().expect("Provide hex-encoded key")[..];
-
-    println!("Encrypting {} into {}...", input_path, output_path);
-
-    let mut buf = [0u8; 16];
-    let key_bytes = mixed::decode(key, &mut buf).unwrap();
-    encrypt(
-        input_path,
-        output_path,
-        key_bytes.try_into().map_err(|_| {
-            io::Error::new(io::ErrorKind::Other, "Wrong key length: 16 bytes expected")
-        })?,
-    )?;
-    Ok(())
+    let input_path = &args.next().unwrap_or_else(|| usage("Provide input path"))[..];
+    let output_path = &args.next().unwrap_or_else(|| usage("Provide output path"))[..];
+
+    let mut key = None;
+    let mut password = false;
+    let mut fd = None;
+    let mut iterations = None;
+    let mut algorithm = Algorithm::Aes128Gcm;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| usage(&format!("Missing value for {}", arg)))
+        };
+        match &arg[..] {
+            "--password" => password = true,
+            "--password-fd" => {
+                fd = Some(
+                    value()
+                        .parse()
+                        .unwrap_or_else(|_| usage("Invalid file descriptor")),
+                )
+            }
+            "--iterations" => {
+                iterations = Some(
+                    value()
+                        .parse()
+                        .ok()
+                        .filter(|n| (MIN_ITERATIONS..=MAX_ITERATIONS).contains(n))
+                        .unwrap_or_else(|| {
+                            usage(&format!(
+                                "Iteration count must be between {} and {}",
+                                MIN_ITERATIONS, MAX_ITERATIONS
+                            ))
+                        }),
+                )
+            }
+            "--algorithm" => {
+                algorithm = value()
+                    .parse::<Algorithm>()
+                    .unwrap_or_else(|err| usage(&err.to_string()))
+            }
+            _ if key.is_none() && !arg.starts_with("--") => key = Some(arg),
+            _ => usage(&format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    if password && key.is_some() {
+        usage("Give either a key or --password, not both");
+    }
+    if !password && (fd.is_some() || iterations.is_some()) {
+        usage("--password-fd and --iterations need --password");
+    }
+
+    println!(
+        "Encrypting {} into {} with {}...",
+        input_path, output_path, algorithm
+    );
+
+    if password {
+        let password = read_password(fd, true)?;
+        return encrypt_with_password(
+            input_path,
+            output_path,
+            password.as_bytes(),
+            iterations.unwrap_or(DEFAULT_ITERATIONS),
+            algorithm,
+        );
+    }
+
+    let key = key.unwrap_or_else(|| usage("Provide hex-encoded key or --password"));
+    let mut buf = [0u8; 64];
+    let key_bytes =
+        mixed::decode(&key, &mut buf).unwrap_or_else(|_| usage("Invalid hex-encoded key"));
+    encrypt(input_path, output_path, key_bytes, algorithm)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("ring_enc: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
 }
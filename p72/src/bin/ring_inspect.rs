@@ -0,0 +1,25 @@
+use p72::inspect;
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = env::args();
+
+    args.next().unwrap();
+    let Some(input_path) = args.next() else {
+        eprintln!("ring_inspect: Provide input path");
+        eprintln!("Usage: ring_inspect INPUT");
+        return ExitCode::from(1);
+    };
+
+    match inspect(&input_path) {
+        Ok(header) => {
+            println!("{}", header);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("ring_inspect: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
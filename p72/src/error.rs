@@ -0,0 +1,140 @@
+use std::fmt;
+use std::io;
+
+/// error from encrypting, decrypting or inspecting a file
+#[derive(Debug)]
+pub enum Error {
+    /// reading the input or writing the output failed
+    Io(io::Error),
+    /// a chunk failed to authenticate: the key or password is wrong, or the file was changed
+    Authentication,
+    /// the input is not a well-formed encrypted file
+    Malformed(&'static str),
+    /// the input ends inside the header or a chunk
+    Truncated,
+    /// the input uses a format version this crate does not know
+    UnsupportedVersion(u8),
+    /// the key does not have the length the algorithm takes
+    KeyLength { expected: usize, actual: usize },
+    /// a key was given for a file protected by a password
+    PasswordRequired,
+    /// a password was given for a file protected by a key
+    KeyRequired,
+    /// the password could not be read, or was refused
+    Password(&'static str),
+    /// the input has more chunks than the counter can number
+    TooManyChunks,
+    /// the system random number generator failed
+    Rng,
+}
+
+impl Error {
+    /// process exit code for the binaries: 2 for I/O, 3 for authentication failure, 4 for
+    /// input that is malformed, truncated or too large, 5 for problems with the key or
+    /// password and 6 for the random number generator; 1 is left for usage errors
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Io(_) => 2,
+            Error::Authentication => 3,
+            Error::Malformed(_)
+            | Error::Truncated
+            | Error::UnsupportedVersion(_)
+            | Error::TooManyChunks => 4,
+            Error::KeyLength { .. }
+            | Error::PasswordRequired
+            | Error::KeyRequired
+            | Error::Password(_) => 5,
+            Error::Rng => 6,
+        }
+    }
+
+    /// as `From<io::Error>`, but counting an early end of input as truncation
+    pub(crate) fn reading(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Error::Truncated,
+            _ => Error::Io(err),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Authentication => write!(
+                f,
+                "Authentication failed: wrong key or password, or the file was modified"
+            ),
+            Error::Malformed(reason) => write!(f, "Malformed input: {}", reason),
+            Error::Truncated => write!(f, "Truncated input"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "Unsupported format version {}", version)
+            }
+            Error::KeyLength { expected, actual } => write!(
+                f,
+                "Wrong key length: {} bytes expected, {} given",
+                expected, actual
+            ),
+            Error::PasswordRequired => write!(f, "File is protected by a password, not a key"),
+            Error::KeyRequired => write!(f, "File is protected by a key, not a password"),
+            Error::Password(reason) => write!(f, "{}", reason),
+            Error::TooManyChunks => write!(f, "Input too large: too many chunks"),
+            Error::Rng => write!(f, "System random number generator failed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<getrandom::Error> for Error {
+    fn from(_: getrandom::Error) -> Self {
+        Error::Rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test that each kind of failure gets its own exit code
+    #[test]
+    fn exit_code_test() {
+        let io_error = Error::from(io::Error::other("disk full"));
+        let codes = [
+            io_error.exit_code(),
+            Error::Authentication.exit_code(),
+            Error::Truncated.exit_code(),
+            Error::KeyRequired.exit_code(),
+            Error::Rng.exit_code(),
+        ];
+        for (i, code) in codes.iter().enumerate() {
+            assert!(*code > 1);
+            assert!(!codes[..i].contains(code));
+        }
+        assert_eq!(
+            Error::Malformed("x").exit_code(),
+            Error::Truncated.exit_code()
+        );
+        assert!(matches!(
+            Error::reading(io::ErrorKind::UnexpectedEof.into()),
+            Error::Truncated
+        ));
+        assert!(matches!(
+            Error::reading(io::ErrorKind::PermissionDenied.into()),
+            Error::Io(_)
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+//! Self-describing header at the start of every encrypted file.
+//!
+//! Version 1 is laid out as the magic bytes `P72E`, the version byte, the algorithm byte, the
+//! big-endian chunk size as four bytes, the key derivation byte (then, for PBKDF2, the
+//! big-endian iteration count as four bytes and the salt) and finally the nonce prefix. The
+//! whole header is passed as associated data to every chunk, so changing any of it makes
+//! decryption fail.
+
+use crate::password::{MAX_ITERATIONS, SALT_LEN};
+use crate::Error;
+use ring::aead;
+use std::fmt;
+use std::io::Read;
+use std::num::NonZeroU32;
+use std::str::FromStr;
+
+/// bytes that open every encrypted file
+pub const MAGIC: [u8; 4] = *b"P72E";
+
+/// format version written by this crate
+pub const VERSION: u8 = 1;
+
+/// random bytes at the start of each nonce, shared by all chunks of a file
+pub const NONCE_PREFIX_LEN: usize = aead::NONCE_LEN - 5;
+
+/// largest chunk size accepted when decrypting, which bounds the memory used
+pub const MAX_CHUNK_SIZE: u32 = 1 << 24;
+
+const KDF_NONE: u8 = 0;
+const KDF_PBKDF2_HMAC_SHA256: u8 = 1;
+
+/// error from parsing an unknown algorithm name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseAlgorithmError;
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown algorithm: expected AES-128-GCM, AES-256-GCM or ChaCha20-Poly1305"
+        )
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
+
+/// authenticated encryption algorithm used for the chunks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Aes128Gcm,
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [
+        Algorithm::Aes128Gcm,
+        Algorithm::Aes256Gcm,
+        Algorithm::ChaCha20Poly1305,
+    ];
+
+    fn id(self) -> u8 {
+        match self {
+            Algorithm::Aes128Gcm => 1,
+            Algorithm::Aes256Gcm => 2,
+            Algorithm::ChaCha20Poly1305 => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, Error> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.id() == id)
+            .ok_or(Error::Malformed("Unknown algorithm"))
+    }
+
+    pub(crate) fn aead(self) -> &'static aead::Algorithm {
+        match self {
+            Algorithm::Aes128Gcm => &aead::AES_128_GCM,
+            Algorithm::Aes256Gcm => &aead::AES_256_GCM,
+            Algorithm::ChaCha20Poly1305 => &aead::CHACHA20_POLY1305,
+        }
+    }
+
+    /// length in bytes of the keys this algorithm takes
+    pub fn key_len(self) -> usize {
+        self.aead().key_len()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::Aes128Gcm => "AES-128-GCM",
+            Algorithm::Aes256Gcm => "AES-256-GCM",
+            Algorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// parses an algorithm name as displayed, ignoring case
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    fn from_str(name: &str) -> Result<Self, ParseAlgorithmError> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+            .ok_or(ParseAlgorithmError)
+    }
+}
+
+/// how the key of a file was obtained
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// the key was given directly
+    None,
+    /// the key was derived from a password with PBKDF2-HMAC-SHA256
+    Pbkdf2 {
+        iterations: NonZeroU32,
+        salt: [u8; SALT_LEN],
+    },
+}
+
+impl fmt::Display for Kdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kdf::None => write!(f, "none (raw key)"),
+            Kdf::Pbkdf2 { iterations, salt } => {
+                write!(f, "PBKDF2-HMAC-SHA256, {} iterations, salt ", iterations)?;
+                salt.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+        }
+    }
+}
+
+/// parsed file header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub algorithm: Algorithm,
+    pub chunk_size: u32,
+    pub kdf: Kdf,
+    pub nonce_prefix: [u8; NONCE_PREFIX_LEN],
+}
+
+impl Header {
+    /// serialised header, which is also the associated data of every chunk
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(self.version);
+        bytes.push(self.algorithm.id());
+        bytes.extend_from_slice(&self.chunk_size.to_be_bytes());
+        match self.kdf {
+            Kdf::None => bytes.push(KDF_NONE),
+            Kdf::Pbkdf2 { iterations, salt } => {
+                bytes.push(KDF_PBKDF2_HMAC_SHA256);
+                bytes.extend_from_slice(&iterations.get().to_be_bytes());
+                bytes.extend_from_slice(&salt);
+            }
+        }
+        bytes.extend_from_slice(&self.nonce_prefix);
+        bytes
+    }
+
+    /// reads a header, checking the magic bytes and parsing the rest as the version it names
+    pub fn read_from(input: &mut impl Read) -> Result<Self, Error> {
+        let mut magic = [0u8; 4];
+        read_exact(input, &mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::Malformed("Not an encrypted file"));
+        }
+        let version = read_u8(input)?;
+        match version {
+            1 => Self::read_v1(input),
+            _ => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    fn read_v1(input: &mut impl Read) -> Result<Self, Error> {
+        let algorithm = Algorithm::from_id(read_u8(input)?)?;
+        let chunk_size = read_u32(input)?;
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(Error::Malformed("Chunk size out of range"));
+        }
+        let kdf = match read_u8(input)? {
+            KDF_NONE => Kdf::None,
+            KDF_PBKDF2_HMAC_SHA256 => {
+                let iterations = NonZeroU32::new(read_u32(input)?)
+                    .filter(|iterations| *iterations <= MAX_ITERATIONS)
+                    .ok_or(Error::Malformed("PBKDF2 iterations out of range"))?;
+                let mut salt = [0u8; SALT_LEN];
+                read_exact(input, &mut salt)?;
+                Kdf::Pbkdf2 { iterations, salt }
+            }
+            _ => return Err(Error::Malformed("Unknown key derivation")),
+        };
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        read_exact(input, &mut nonce_prefix)?;
+        Ok(Self {
+            version: 1,
+            algorithm,
+            chunk_size,
+            kdf,
+            nonce_prefix,
+        })
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "format version: {}", self.version)?;
+        writeln!(f, "algorithm: {}", self.algorithm)?;
+        writeln!(f, "chunk size: {} bytes", self.chunk_size)?;
+        writeln!(f, "key derivation: {}", self.kdf)?;
+        write!(f, "nonce prefix: ")?;
+        self.nonce_prefix
+            .iter()
+            .try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+fn read_exact(input: &mut impl Read, buf: &mut [u8]) -> Result<(), Error> {
+    input.read_exact(buf).map_err(Error::reading)
+}
+
+fn read_u8(input: &mut impl Read) -> Result<u8, Error> {
+    let mut byte = [0u8; 1];
+    read_exact(input, &mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_u32(input: &mut impl Read) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+    read_exact(input, &mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password_header() -> Header {
+        Header {
+            version: VERSION,
+            algorithm: Algorithm::Aes128Gcm,
+            chunk_size: 4096,
+            kdf: Kdf::Pbkdf2 {
+                iterations: NonZeroU32::new(1000).unwrap(),
+                salt: [0xab; SALT_LEN],
+            },
+            nonce_prefix: [1, 2, 3, 4, 5, 6, 7],
+        }
+    }
+
+    // test the exact layout and that parsing gives the header back
+    #[test]
+    fn round_trip_test() {
+        let header = password_header();
+        let bytes = header.to_bytes();
+        let mut expected = b"P72E\x01\x01\x00\x00\x10\x00\x01\x00\x00\x03\xe8".to_vec();
+        expected.extend_from_slice(&[0xab; SALT_LEN]);
+        expected.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(bytes, expected);
+        assert_eq!(Header::read_from(&mut &bytes[..]).unwrap(), header);
+        let raw = Header {
+            kdf: Kdf::None,
+            ..header
+        };
+        let bytes = raw.to_bytes();
+        assert_eq!(bytes.len(), 18);
+        assert_eq!(Header::read_from(&mut &bytes[..]).unwrap(), raw);
+    }
+
+    // test that bad magic, unknown versions and out-of-range fields are rejected
+    #[test]
+    fn malformed_test() {
+        let bytes = password_header().to_bytes();
+        let parse = |change: &dyn Fn(&mut Vec<u8>)| {
+            let mut changed = bytes.clone();
+            change(&mut changed);
+            Header::read_from(&mut &changed[..]).unwrap_err()
+        };
+        let malformed = |err| matches!(err, Error::Malformed(_));
+        assert!(malformed(parse(&|b| b[0] = b'X')));
+        assert!(matches!(parse(&|b| b[4] = 2), Error::UnsupportedVersion(2)));
+        assert!(malformed(parse(&|b| b[5] = 0)));
+        assert!(malformed(parse(&|b| b[5] = 4)));
+        assert!(malformed(parse(&|b| b[6..10].fill(0))));
+        assert!(malformed(parse(&|b| b[6] = 0xff)));
+        assert!(malformed(parse(&|b| b[10] = 9)));
+        assert!(malformed(parse(&|b| b[11..15].fill(0))));
+        assert!(malformed(parse(&|b| b[11..15].fill(0xff))));
+        let above_max = MAX_ITERATIONS.get() + 1;
+        assert!(malformed(parse(
+            &|b| b[11..15].copy_from_slice(&above_max.to_be_bytes())
+        )));
+        assert!(matches!(parse(&|b| b.truncate(20)), Error::Truncated));
+        assert!(matches!(parse(&|b| b.truncate(0)), Error::Truncated));
+    }
+
+    // test the text printed by inspect
+    #[test]
+    fn display_test() {
+        assert_eq!(
+            password_header().to_string(),
+            concat!(
+                "format version: 1\n",
+                "algorithm: AES-128-GCM\n",
+                "chunk size: 4096 bytes\n",
+                "key derivation: PBKDF2-HMAC-SHA256, 1000 iterations, ",
+                "salt abababababababababababababababab\n",
+                "nonce prefix: 01020304050607",
+            )
+        );
+    }
+
+    // test algorithm ids, names and key lengths
+    #[test]
+    fn algorithm_test() {
+        for (algorithm, id, key_len) in [
+            (Algorithm::Aes128Gcm, 1, 16),
+            (Algorithm::Aes256Gcm, 2, 32),
+            (Algorithm::ChaCha20Poly1305, 3, 32),
+        ] {
+            let header = Header {
+                algorithm,
+                ..password_header()
+            };
+            let bytes = header.to_bytes();
+            assert_eq!(bytes[5], id);
+            assert_eq!(Header::read_from(&mut &bytes[..]).unwrap(), header);
+            assert_eq!(algorithm.key_len(), key_len);
+            assert_eq!(
+                algorithm.to_string().parse::<Algorithm>().unwrap(),
+                algorithm
+            );
+        }
+        assert_eq!(
+            "chacha20-poly1305".parse::<Algorithm>().unwrap(),
+            Algorithm::ChaCha20Poly1305
+        );
+        assert_eq!("aes-192-gcm".parse::<Algorithm>(), Err(ParseAlgorithmError));
+    }
+}
@@ -1,74 +1,613 @@
+//! Chunked authenticated encryption of files, in the style of the STREAM construction.
+//!
+//! The output starts with a `Header`, followed by the plaintext sealed in chunks of
+//! `CHUNK_SIZE` bytes, each with its own tag and the header as associated data; only the last
+//! chunk may be shorter, and an empty input still gives one empty last chunk. The nonce of
+//! each chunk is the prefix from the header, a big-endian chunk counter and a flag byte that
+//! is set only on the last chunk, so chunks that are reordered, dropped from the end or
+//! appended after the last one fail to open.
+
+mod error;
+pub mod header;
+pub mod password;
+
+pub use error::Error;
+
 use getrandom::getrandom;
-use ring::aead::{self, BoundKey};
+use header::{Algorithm, Header, Kdf, NONCE_PREFIX_LEN};
+use password::SALT_LEN;
+use ring::aead;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
+use std::num::NonZeroU32;
 
-struct MyNonce([u8; aead::NONCE_LEN]);
+/// plaintext bytes sealed under each tag when encrypting
+pub const CHUNK_SIZE: usize = 64 * 1024;
 
-impl aead::NonceSequence for MyNonce {
-    fn advance(&mut self) -> Result<aead::Nonce, ring::error::Unspecified> {
-        Ok(aead::Nonce::assume_unique_for_key(self.0))
+/// tag length of every supported algorithm
+const TAG_LEN: usize = 16;
+
+/// nonce for chunk `counter`: prefix, big-endian counter and last-chunk flag
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> aead::Nonce {
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..aead::NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[aead::NONCE_LEN - 1] = u8::from(last);
+    aead::Nonce::assume_unique_for_key(nonce)
+}
+
+/// fills `buf` from `reader` until it is full or the input ends, returning the bytes read
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
     }
+    Ok(filled)
 }
 
-pub fn encrypt(input_path: &str, output_path: &str, key_bytes: &[u8; 16]) -> std::io::Result<()> {
-    let mut in_file = File::open(input_path)?;
-    let mut contents: Vec<u8> = vec![];
-    in_file.read_to_end(&mut contents)?;
+fn next_counter(counter: u32) -> Result<u32, Error> {
+    counter.checked_add(1).ok_or(Error::TooManyChunks)
+}
 
-    let mut nonce_seed = [0u8; aead::NONCE_LEN];
-    getrandom(&mut nonce_seed).unwrap();
-    let nonce_sequence = MyNonce(nonce_seed);
+/// builds an `algorithm` key, checking its length
+fn aead_key(algorithm: Algorithm, key_bytes: &[u8]) -> Result<aead::LessSafeKey, Error> {
+    let key_len_error = Error::KeyLength {
+        expected: algorithm.key_len(),
+        actual: key_bytes.len(),
+    };
+    let key = aead::UnboundKey::new(algorithm.aead(), key_bytes).map_err(|_| key_len_error)?;
+    Ok(aead::LessSafeKey::new(key))
+}
 
-    let key = aead::UnboundKey::new(&aead::AES_128_GCM, key_bytes).unwrap();
-    let mut enc_key = aead::SealingKey::new(key, nonce_sequence);
+/// writes a fresh header for `algorithm` and `kdf` and seals everything read from `input`
+/// after it into `output`, holding at most two chunks in memory
+fn seal_stream(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    key_bytes: &[u8],
+    algorithm: Algorithm,
+    kdf: Kdf,
+) -> Result<(), Error> {
+    let key = aead_key(algorithm, key_bytes)?;
+    let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+    getrandom(&mut nonce_prefix)?;
+    let header = Header {
+        version: header::VERSION,
+        algorithm,
+        chunk_size: CHUNK_SIZE as u32,
+        kdf,
+        nonce_prefix,
+    };
+    let aad = header.to_bytes();
+    output.write_all(&aad)?;
 
-    let mut out_file = File::options()
-        .read(false)
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(output_path)?;
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    let mut next = vec![0u8; CHUNK_SIZE];
+    let mut len = read_full(input, &mut chunk)?;
+    let mut counter: u32 = 0;
+    loop {
+        // a chunk is the last one when nothing follows it
+        let next_len = if len == CHUNK_SIZE {
+            read_full(input, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let tag = key
+            .seal_in_place_separate_tag(
+                chunk_nonce(&header.nonce_prefix, counter, last),
+                aead::Aad::from(&aad),
+                &mut chunk[..len],
+            )
+            .expect("Chunks are within the message size limit");
+        output.write_all(&chunk[..len])?;
+        output.write_all(tag.as_ref())?;
+        if last {
+            return Ok(());
+        }
+        std::mem::swap(&mut chunk, &mut next);
+        len = next_len;
+        counter = next_counter(counter)?;
+    }
+}
 
-    let tag = enc_key
-        .seal_in_place_separate_tag(aead::Aad::empty(), &mut contents)
-        .unwrap();
-    out_file.write_all(&nonce_seed)?;
-    out_file.write_all(tag.as_ref())?;
-    out_file.write_all(&contents)?;
+/// opens the chunks of a version 1 file that follow `header` into `output`, holding at most
+/// two chunks in memory
+fn open_stream_v1(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    header: &Header,
+    key_bytes: &[u8],
+) -> Result<(), Error> {
+    let aad = header.to_bytes();
+    let key = aead_key(header.algorithm, key_bytes)?;
 
-    Ok(())
+    let sealed_len = header.chunk_size as usize + TAG_LEN;
+    let mut chunk = vec![0u8; sealed_len];
+    let mut next = vec![0u8; sealed_len];
+    let mut len = read_full(input, &mut chunk)?;
+    let mut counter: u32 = 0;
+    loop {
+        let next_len = if len == sealed_len {
+            read_full(input, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        if len < TAG_LEN {
+            return Err(Error::Truncated);
+        }
+        let plain = key
+            .open_in_place(
+                chunk_nonce(&header.nonce_prefix, counter, last),
+                aead::Aad::from(&aad),
+                &mut chunk[..len],
+            )
+            .map_err(|_| Error::Authentication)?;
+        output.write_all(plain)?;
+        if last {
+            return Ok(());
+        }
+        std::mem::swap(&mut chunk, &mut next);
+        len = next_len;
+        counter = next_counter(counter)?;
+    }
 }
 
-pub fn decrypt(input_path: &str, output_path: &str, key_bytes: &[u8; 16]) -> std::io::Result<()> {
-    let mut in_file = File::open(input_path)?;
-    let mut contents: Vec<u8> = vec![];
+/// reads the header, works out the key from it with `key_for`, and opens the rest of `input`
+/// in the way its format version calls for
+fn open_stream(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    key_for: impl FnOnce(&Header) -> Result<Vec<u8>, Error>,
+) -> Result<(), Error> {
+    let header = Header::read_from(input)?;
+    let key_bytes = key_for(&header)?;
+    match header.version {
+        1 => open_stream_v1(input, output, &header, &key_bytes),
+        version => Err(Error::UnsupportedVersion(version)),
+    }
+}
 
-    let mut nonce_seed = [0u8; aead::NONCE_LEN];
-    in_file.read_exact(&mut nonce_seed)?;
-    let nonce_sequence = MyNonce(nonce_seed);
+/// encrypts everything read from `input` into `output` with `algorithm` under a raw key,
+/// which must have the length the algorithm takes
+pub fn encrypt_stream(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    key_bytes: &[u8],
+    algorithm: Algorithm,
+) -> Result<(), Error> {
+    seal_stream(input, output, key_bytes, algorithm, Kdf::None)
+}
 
-    let mut tag_bytes = [0u8; aead::MAX_TAG_LEN];
-    in_file.read_exact(&mut tag_bytes)?;
+/// encrypts everything read from `input` into `output` under a key derived from `password`
+/// with a fresh random salt; files made with more than `MAX_ITERATIONS` iterations will not
+/// decrypt
+pub fn encrypt_stream_with_password(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    password: &[u8],
+    iterations: NonZeroU32,
+    algorithm: Algorithm,
+) -> Result<(), Error> {
+    let (key_bytes, kdf) = password_key(password, iterations, algorithm)?;
+    seal_stream(input, output, &key_bytes, algorithm, kdf)
+}
 
-    in_file.read_to_end(&mut contents)?;
-    contents.extend_from_slice(&tag_bytes);
+/// derives a key for `algorithm` from `password` with a fresh random salt, returning it with
+/// the key derivation to record in the header
+fn password_key(
+    password: &[u8],
+    iterations: NonZeroU32,
+    algorithm: Algorithm,
+) -> Result<(Vec<u8>, Kdf), Error> {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom(&mut salt)?;
+    let key_bytes = password::derive_key(password, &salt, iterations, algorithm.key_len());
+    Ok((key_bytes, Kdf::Pbkdf2 { iterations, salt }))
+}
 
-    let key = aead::UnboundKey::new(&aead::AES_128_GCM, key_bytes).unwrap();
-    let mut dec_key = aead::OpeningKey::new(key, nonce_sequence);
+/// decrypts everything read from `input` into `output` with a raw key, using the algorithm
+/// named in its header; chunks are written out as they are verified, so on error `output`
+/// holds a prefix of the plaintext that must be discarded
+pub fn decrypt_stream(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    key_bytes: &[u8],
+) -> Result<(), Error> {
+    open_stream(input, output, |header| match header.kdf {
+        Kdf::None => Ok(key_bytes.to_vec()),
+        Kdf::Pbkdf2 { .. } => Err(Error::PasswordRequired),
+    })
+}
+
+/// as `decrypt_stream`, re-deriving the key from `password` and the salt stored in the input
+pub fn decrypt_stream_with_password(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    password: &[u8],
+) -> Result<(), Error> {
+    open_stream(input, output, |header| match header.kdf {
+        Kdf::Pbkdf2 { iterations, salt } => Ok(password::derive_key(
+            password,
+            &salt,
+            iterations,
+            header.algorithm.key_len(),
+        )),
+        Kdf::None => Err(Error::KeyRequired),
+    })
+}
 
-    let mut out_file = File::options()
+fn create_output(output_path: &str) -> io::Result<File> {
+    File::options()
         .read(false)
         .write(true)
         .create(true)
         .truncate(true)
-        .open(output_path)?;
+        .open(output_path)
+}
+
+/// runs `process` from `input_path` into `output_path`, removing the output again if it fails
+fn process_file(
+    input_path: &str,
+    output_path: &str,
+    process: impl FnOnce(&mut File, &mut File) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut in_file = File::open(input_path)?;
+    let mut out_file = create_output(output_path)?;
+    let result = process(&mut in_file, &mut out_file);
+    if result.is_err() {
+        drop(out_file);
+        let _ = std::fs::remove_file(output_path);
+    }
+    result
+}
+
+/// encrypts `input_path` into `output_path`, checking the key before the output is touched
+/// and removing the output again if encryption fails
+pub fn encrypt(
+    input_path: &str,
+    output_path: &str,
+    key_bytes: &[u8],
+    algorithm: Algorithm,
+) -> Result<(), Error> {
+    aead_key(algorithm, key_bytes)?;
+    process_file(input_path, output_path, |input, output| {
+        encrypt_stream(input, output, key_bytes, algorithm)
+    })
+}
+
+/// as `encrypt`, deriving the key from `password` before the output is touched
+pub fn encrypt_with_password(
+    input_path: &str,
+    output_path: &str,
+    password: &[u8],
+    iterations: NonZeroU32,
+    algorithm: Algorithm,
+) -> Result<(), Error> {
+    let (key_bytes, kdf) = password_key(password, iterations, algorithm)?;
+    process_file(input_path, output_path, |input, output| {
+        seal_stream(input, output, &key_bytes, algorithm, kdf)
+    })
+}
 
-    let ct = dec_key
-        .open_in_place(aead::Aad::empty(), &mut contents)
+/// decrypts `input_path` into `output_path`, removing the output again if any chunk fails
+pub fn decrypt(input_path: &str, output_path: &str, key_bytes: &[u8]) -> Result<(), Error> {
+    process_file(input_path, output_path, |input, output| {
+        decrypt_stream(input, output, key_bytes)
+    })
+}
+
+/// as `decrypt`, for a file encrypted with a password
+pub fn decrypt_with_password(
+    input_path: &str,
+    output_path: &str,
+    password: &[u8],
+) -> Result<(), Error> {
+    process_file(input_path, output_path, |input, output| {
+        decrypt_stream_with_password(input, output, password)
+    })
+}
+
+/// reads the header of `input_path`, which needs no key
+pub fn inspect(input_path: &str) -> Result<Header, Error> {
+    Header::read_from(&mut File::open(input_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 16] = *b"0123456789abcdef";
+
+    // header ahead of the first chunk, with a raw key
+    const HEADER_LEN: usize = 11 + NONCE_PREFIX_LEN;
+
+    fn seal(plain: &[u8]) -> Vec<u8> {
+        let mut sealed = vec![];
+        encrypt_stream(&mut &plain[..], &mut sealed, &KEY, Algorithm::Aes128Gcm).unwrap();
+        sealed
+    }
+
+    fn open(sealed: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut plain = vec![];
+        decrypt_stream(&mut &sealed[..], &mut plain, &KEY)?;
+        Ok(plain)
+    }
+
+    // path in the temporary directory unique to this process and `name`
+    fn temp_path(name: &str) -> String {
+        let file = format!("p72-test-{}-{}", std::process::id(), name);
+        std::env::temp_dir().join(file).to_str().unwrap().to_owned()
+    }
+
+    fn sealed_len(plain_len: usize) -> usize {
+        let chunks = plain_len.div_ceil(CHUNK_SIZE).max(1);
+        HEADER_LEN + plain_len + chunks * TAG_LEN
+    }
+
+    // test round trips around the chunk boundaries, including the empty input
+    #[test]
+    fn round_trip_test() {
+        for len in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            3 * CHUNK_SIZE,
+        ] {
+            let plain: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let sealed = seal(&plain);
+            assert_eq!(sealed.len(), sealed_len(len));
+            assert_eq!(open(&sealed).unwrap(), plain);
+        }
+    }
+
+    // test that a wrong key or a flipped bit fails authentication
+    #[test]
+    fn tamper_test() {
+        let sealed = seal(b"attack at dawn");
+        let mut plain = vec![];
+        let wrong_key = [0u8; 16];
+        let err = decrypt_stream(&mut &sealed[..], &mut plain, &wrong_key).unwrap_err();
+        assert!(matches!(err, Error::Authentication));
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 1;
+            assert!(open(&tampered).is_err());
+        }
+    }
+
+    // test that reordered, dropped and appended chunks are detected
+    #[test]
+    fn chunk_order_test() {
+        let plain = vec![7u8; 3 * CHUNK_SIZE + 10];
+        let sealed = seal(&plain);
+        let (prefix, body) = sealed.split_at(HEADER_LEN);
+        let chunks: Vec<&[u8]> = body.chunks(CHUNK_SIZE + TAG_LEN).collect();
+        assert_eq!(chunks.len(), 4);
+        let join = |order: &[usize]| {
+            let mut joined = prefix.to_vec();
+            for &i in order {
+                joined.extend_from_slice(chunks[i]);
+            }
+            joined
+        };
+        assert_eq!(open(&join(&[0, 1, 2, 3])).unwrap(), plain);
+        // swapped
+        assert!(open(&join(&[1, 0, 2, 3])).is_err());
+        // truncated at a chunk boundary, so the new last chunk lacks the flag
+        assert!(open(&join(&[0, 1, 2])).is_err());
+        // last chunk dropped and replaced by an earlier one
+        assert!(open(&join(&[0, 1, 2, 2])).is_err());
+        // chunk appended after the last one
+        assert!(open(&join(&[0, 1, 2, 3, 3])).is_err());
+        // truncated inside a chunk fails authentication, before the first tag or inside the
+        // header it is reported as truncation
+        assert!(matches!(
+            open(&sealed[..sealed.len() - 1]),
+            Err(Error::Authentication)
+        ));
+        assert!(matches!(
+            open(&sealed[..HEADER_LEN + TAG_LEN - 1]),
+            Err(Error::Truncated)
+        ));
+        assert!(matches!(
+            open(&sealed[..HEADER_LEN - 1]),
+            Err(Error::Truncated)
+        ));
+    }
+
+    // test that a full-size last chunk is told apart from one that has more chunks after it
+    #[test]
+    fn exact_chunk_test() {
+        let sealed = seal(&[1u8; 2 * CHUNK_SIZE]);
+        let one_chunk = &sealed[..HEADER_LEN + CHUNK_SIZE + TAG_LEN];
+        assert!(open(one_chunk).is_err());
+        assert_eq!(open(&sealed).unwrap(), vec![1u8; 2 * CHUNK_SIZE]);
+    }
+
+    // test password mode, and that each mode refuses files made by the other
+    #[test]
+    fn password_test() {
+        let iterations = NonZeroU32::new(1000).unwrap();
+        let mut sealed = vec![];
+        encrypt_stream_with_password(
+            &mut &b"hunter2"[..],
+            &mut sealed,
+            b"pw",
+            iterations,
+            Algorithm::Aes128Gcm,
+        )
+        .unwrap();
+        let header = Header::read_from(&mut &sealed[..]).unwrap();
+        let Kdf::Pbkdf2 {
+            iterations: _,
+            salt,
+        } = header.kdf
+        else {
+            panic!("Expected a password header");
+        };
+        assert_eq!(header.kdf, Kdf::Pbkdf2 { iterations, salt });
+        let mut plain = vec![];
+        decrypt_stream_with_password(&mut &sealed[..], &mut plain, b"pw").unwrap();
+        assert_eq!(plain, b"hunter2");
+        // salts are random, so the same password gives a different key each time
+        let mut resealed = vec![];
+        encrypt_stream_with_password(
+            &mut &b"hunter2"[..],
+            &mut resealed,
+            b"pw",
+            iterations,
+            Algorithm::Aes128Gcm,
+        )
         .unwrap();
+        let reheader = Header::read_from(&mut &resealed[..]).unwrap();
+        assert_ne!(header.kdf, reheader.kdf);
+        let err = decrypt_stream_with_password(&mut &sealed[..], &mut vec![], b"pw2").unwrap_err();
+        assert!(matches!(err, Error::Authentication));
+        // a changed iteration count or salt derives a different key
+        let mut tampered = sealed.clone();
+        tampered[14] ^= 1;
+        assert!(decrypt_stream_with_password(&mut &tampered[..], &mut vec![], b"pw").is_err());
+        let mut tampered = sealed.clone();
+        tampered[15] ^= 1;
+        assert!(decrypt_stream_with_password(&mut &tampered[..], &mut vec![], b"pw").is_err());
+        let err = decrypt_stream(&mut &sealed[..], &mut vec![], &KEY).unwrap_err();
+        assert!(matches!(err, Error::PasswordRequired));
+        let err =
+            decrypt_stream_with_password(&mut &seal(b"")[..], &mut vec![], b"pw").unwrap_err();
+        assert!(matches!(err, Error::KeyRequired));
+    }
+
+    // test that the header is authenticated and can be read without the key
+    #[test]
+    fn header_test() {
+        let sealed = seal(&[5u8; 100]);
+        let header = Header::read_from(&mut &sealed[..]).unwrap();
+        assert_eq!(header.version, header::VERSION);
+        assert_eq!(header.algorithm, Algorithm::Aes128Gcm);
+        assert_eq!(header.chunk_size, CHUNK_SIZE as u32);
+        assert_eq!(header.kdf, Kdf::None);
+        assert_eq!(sealed[..HEADER_LEN], header.to_bytes());
+        // a different but valid chunk size or nonce prefix fails to authenticate
+        for i in [8, HEADER_LEN - 1] {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 1;
+            Header::read_from(&mut &tampered[..]).unwrap();
+            assert!(matches!(open(&tampered), Err(Error::Authentication)));
+        }
+        let mut tampered = sealed.clone();
+        tampered[4] = header::VERSION + 1;
+        assert!(matches!(
+            open(&tampered),
+            Err(Error::UnsupportedVersion(v)) if v == header::VERSION + 1
+        ));
+    }
+
+    // test that files written with a smaller chunk size, as a later writer might choose,
+    // still open
+    #[test]
+    fn chunk_size_test() {
+        let header = Header {
+            version: header::VERSION,
+            algorithm: Algorithm::Aes128Gcm,
+            chunk_size: 3,
+            kdf: Kdf::None,
+            nonce_prefix: [9; NONCE_PREFIX_LEN],
+        };
+        let aad = header.to_bytes();
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_128_GCM, &KEY).unwrap());
+        let mut sealed = aad.clone();
+        for (counter, piece) in [&b"abc"[..], b"de"].into_iter().enumerate() {
+            let mut piece = piece.to_vec();
+            let nonce = chunk_nonce(&header.nonce_prefix, counter as u32, counter == 1);
+            key.seal_in_place_append_tag(nonce, aead::Aad::from(&aad), &mut piece)
+                .unwrap();
+            sealed.extend_from_slice(&piece);
+        }
+        assert_eq!(open(&sealed).unwrap(), b"abcde");
+    }
+
+    // test every algorithm in both modes, and that keys of the wrong length are refused
+    #[test]
+    fn algorithm_test() {
+        let iterations = NonZeroU32::new(1000).unwrap();
+        let plain = vec![3u8; CHUNK_SIZE + 5];
+        for algorithm in Algorithm::ALL {
+            let key = vec![0x42u8; algorithm.key_len()];
+            let mut sealed = vec![];
+            encrypt_stream(&mut &plain[..], &mut sealed, &key, algorithm).unwrap();
+            assert_eq!(
+                Header::read_from(&mut &sealed[..]).unwrap().algorithm,
+                algorithm
+            );
+            let mut opened = vec![];
+            decrypt_stream(&mut &sealed[..], &mut opened, &key).unwrap();
+            assert_eq!(opened, plain);
 
-    out_file.write_all(ct)?;
+            let mut sealed = vec![];
+            encrypt_stream_with_password(
+                &mut &plain[..],
+                &mut sealed,
+                b"pw",
+                iterations,
+                algorithm,
+            )
+            .unwrap();
+            let mut opened = vec![];
+            decrypt_stream_with_password(&mut &sealed[..], &mut opened, b"pw").unwrap();
+            assert_eq!(opened, plain);
 
-    Ok(())
+            let short_key = &key[..key.len() - 1];
+            let err =
+                encrypt_stream(&mut &plain[..], &mut vec![], short_key, algorithm).unwrap_err();
+            assert!(matches!(
+                err,
+                Error::KeyLength { expected, actual } if expected == actual + 1
+            ));
+        }
+        // a 32-byte key does not open an AES-128-GCM file, and the header names the right one
+        let err = decrypt_stream(&mut &seal(b"")[..], &mut vec![], &[0u8; 32]).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::KeyLength {
+                expected: 16,
+                actual: 32
+            }
+        ));
+        // the algorithm is authenticated, so relabelling a file fails even with a valid key
+        let key = [0x42u8; 32];
+        let mut sealed = vec![];
+        encrypt_stream(&mut &b"x"[..], &mut sealed, &key, Algorithm::Aes256Gcm).unwrap();
+        sealed[5] = 3;
+        let err = decrypt_stream(&mut &sealed[..], &mut vec![], &key).unwrap_err();
+        assert!(matches!(err, Error::Authentication));
+    }
+    // test that a key of the wrong length is reported before an existing output is touched
+    #[test]
+    fn key_length_file_test() {
+        let (input, output) = (temp_path("key-input"), temp_path("key-output"));
+        std::fs::write(&input, b"plain").unwrap();
+        std::fs::write(&output, b"keep me").unwrap();
+        let err = encrypt(&input, &output, &KEY[..15], Algorithm::Aes128Gcm).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::KeyLength {
+                expected: 16,
+                actual: 15
+            }
+        ));
+        assert_eq!(std::fs::read(&output).unwrap(), b"keep me");
+        encrypt(&input, &output, &KEY, Algorithm::Aes128Gcm).unwrap();
+        assert_eq!(std::fs::read(&output).unwrap().len(), sealed_len(5));
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
 }
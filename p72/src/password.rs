@@ -0,0 +1,89 @@
+//! Password mode: reading a password and stretching it into a key with PBKDF2-HMAC-SHA256.
+
+use crate::Error;
+use ring::pbkdf2;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::num::NonZeroU32;
+
+/// bytes of random salt stored with each password-protected file
+pub const SALT_LEN: usize = 16;
+
+/// PBKDF2-HMAC-SHA256 iterations used unless asked otherwise, as recommended by OWASP
+pub const DEFAULT_ITERATIONS: NonZeroU32 = NonZeroU32::new(600_000).unwrap();
+
+/// fewest iterations `ring_enc` will use
+pub const MIN_ITERATIONS: NonZeroU32 = NonZeroU32::new(10_000).unwrap();
+
+/// most iterations accepted when decrypting, which bounds the time spent deriving a key
+/// before any chunk is authenticated
+pub const MAX_ITERATIONS: NonZeroU32 = NonZeroU32::new(10_000_000).unwrap();
+
+/// stretches `password` into a key of `key_len` bytes
+pub(crate) fn derive_key(
+    password: &[u8],
+    salt: &[u8; SALT_LEN],
+    iterations: NonZeroU32,
+    key_len: usize,
+) -> Vec<u8> {
+    let mut key = vec![0u8; key_len];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        password,
+        &mut key,
+    );
+    key
+}
+
+/// reads a password up to the first newline from file descriptor `fd`, or prompts for it on the
+/// terminal without echo if `fd` is `None`; with `confirm`, the prompt asks twice
+pub fn read_password(fd: Option<u32>, confirm: bool) -> Result<String, Error> {
+    let password = match fd {
+        Some(fd) => {
+            let file = File::open(format!("/dev/fd/{}", fd))?;
+            let mut line = String::new();
+            BufReader::new(file).read_line(&mut line)?;
+            let len = line.trim_end_matches(['\n', '\r']).len();
+            line.truncate(len);
+            line
+        }
+        None => {
+            let password = rpassword::prompt_password("Password: ")?;
+            if confirm && rpassword::prompt_password("Confirm password: ")? != password {
+                return Err(Error::Password("Passwords do not match"));
+            }
+            password
+        }
+    };
+    if password.is_empty() {
+        return Err(Error::Password("Empty password"));
+    }
+    Ok(password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test against PBKDF2-HMAC-SHA256 as computed by Python's hashlib
+    #[test]
+    fn derive_key_test() {
+        let salt: [u8; SALT_LEN] = core::array::from_fn(|i| i as u8);
+        assert_eq!(
+            derive_key(b"password", &salt, NonZeroU32::new(1).unwrap(), 16),
+            [
+                0xe1, 0xb0, 0x8f, 0x92, 0xbe, 0x81, 0x74, 0xd9, 0xf4, 0x42, 0xd9, 0x5d, 0x89, 0xaa,
+                0x4c, 0xcd
+            ]
+        );
+        assert_eq!(
+            derive_key(b"password", &salt, NonZeroU32::new(4096).unwrap(), 16),
+            [
+                0x91, 0x70, 0x0e, 0x3c, 0x49, 0x4e, 0x10, 0x07, 0x17, 0x00, 0x86, 0x66, 0x01, 0xef,
+                0xd4, 0x86
+            ]
+        );
+    }
+}